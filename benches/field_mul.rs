@@ -0,0 +1,58 @@
+//! Times a tight chain of multiplications mod the secp256k1 prime, with
+//! `FieldElement` reducing by `%` against `ReducingField` reducing by
+//! Barrett. Run with `cargo bench --bench field_mul`.
+
+use bitcoin_util::barrett::ReducingField;
+use bitcoin_util::ecc::{FieldElement, FieldElementOps};
+use bitcoin_util::secp256k1;
+use std::time::{Duration, Instant};
+
+fn time<F: FnMut()>(name: &str, iterations: u32, mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let per_iter = start.elapsed() / iterations;
+    println!("{:<24} {:>12?} / iter", name, per_iter);
+    per_iter
+}
+
+fn main() {
+    let p = secp256k1::p();
+    let field = ReducingField::new(p.clone());
+    let g = secp256k1::g();
+    let x = g.x.clone().unwrap();
+    let y = g.y.clone().unwrap();
+    let rounds = 10_000;
+
+    let plain_chain = || {
+        let mut acc = x.clone();
+        for _ in 0..rounds {
+            acc = &acc * &y;
+        }
+        acc
+    };
+    let barrett_chain = || {
+        let mut acc = x.num.clone();
+        for _ in 0..rounds {
+            acc = field.mul(&acc, &y.num);
+        }
+        acc
+    };
+    assert_eq!(
+        plain_chain(),
+        FieldElement::from_reduced(barrett_chain(), p.clone())
+    );
+
+    let iterations = 20;
+    let plain = time("FieldElement (%)", iterations, || {
+        plain_chain();
+    });
+    let barrett = time("ReducingField (Barrett)", iterations, || {
+        barrett_chain();
+    });
+    println!(
+        "Barrett speedup over %: {:.2}x",
+        plain.as_secs_f64() / barrett.as_secs_f64()
+    );
+}
@@ -0,0 +1,150 @@
+//! Compares secp256k1 scalar multiplication over the heap-allocated
+//! `FieldElement` (affine and Jacobian) with the same affine double-and-add
+//! over `Fp256`, `u*G + v*P` as two multiplications against Shamir's
+//! trick, a plain multiplication against the GLV split, and the Montgomery
+//! ladder against the precomputed `G` table that signing uses. Run with
+//! `cargo bench`.
+
+use bitcoin_util::ecdsa::PrivateKey;
+use bitcoin_util::fp256::Fp256;
+use bitcoin_util::generator::{mul_generator, GeneratorTable};
+use bitcoin_util::point::{Point, PointOps};
+use bitcoin_util::secp256k1;
+use ibig::UBig;
+use std::time::{Duration, Instant};
+
+type Affine = Option<(Fp256, Fp256)>;
+
+fn add(p: Affine, q: Affine) -> Affine {
+    let ((x1, y1), (x2, y2)) = match (p, q) {
+        (None, _) => return q,
+        (_, None) => return p,
+        (Some(p), Some(q)) => (p, q),
+    };
+    let slope = if x1 == x2 {
+        if y1 != y2 || y1.is_zero() {
+            return None;
+        }
+        let three = Fp256::ONE + Fp256::ONE + Fp256::ONE;
+        three * x1 * x1 / (y1 + y1)
+    } else {
+        (y2 - y1) / (x2 - x1)
+    };
+    let x3 = slope * slope - x1 - x2;
+    Some((x3, slope * (x1 - x3) - y1))
+}
+
+fn scalar_mul(point: Affine, k: &UBig) -> Affine {
+    let mut result = None;
+    let mut addend = point;
+    for i in 0..k.bit_len() {
+        if k.bit(i) {
+            result = add(result, addend);
+        }
+        addend = add(addend, addend);
+    }
+    result
+}
+
+fn time<F: FnMut()>(name: &str, iterations: u32, mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let per_iter = start.elapsed() / iterations;
+    println!("{:<24} {:>12?} / iter", name, per_iter);
+    per_iter
+}
+
+fn main() {
+    let k = secp256k1::n() - UBig::from(12345u32);
+    let g = secp256k1::g();
+    let g_fp = Some((
+        Fp256::new(g.x.clone().unwrap().num).unwrap(),
+        Fp256::new(g.y.clone().unwrap().num).unwrap(),
+    ));
+
+    let expected = g.scalar_mul(&k);
+    let (x, y) = scalar_mul(g_fp, &k).unwrap();
+    assert_eq!(x.to_ubig(), expected.x.clone().unwrap().num);
+    assert_eq!(y.to_ubig(), expected.y.clone().unwrap().num);
+
+    assert_eq!(g.to_jacobian().scalar_mul(&k).to_affine(), expected);
+
+    // every affine addition or doubling divides once; Jacobian coordinates
+    // defer the single inversion to the final to_affine
+    let ones = (0..k.bit_len()).filter(|i| k.bit(*i)).count();
+    println!("inversions: affine {}, jacobian 1", k.bit_len() + ones);
+
+    let iterations = 5;
+    let ubig = time("FieldElement (UBig)", iterations, || {
+        g.scalar_mul(&k);
+    });
+    time("JacobianPoint (UBig)", iterations, || {
+        g.to_jacobian().scalar_mul(&k).to_affine();
+    });
+    let fp256 = time("Fp256", iterations, || {
+        scalar_mul(g_fp, &k);
+    });
+    println!(
+        "Fp256 speedup over affine FieldElement: {:.1}x",
+        ubig.as_secs_f64() / fp256.as_secs_f64()
+    );
+
+    // the shape of ECDSA verification: two full-width scalars
+    let u = secp256k1::n() - UBig::from(999u32);
+    let point = g.to_jacobian().scalar_mul(&k).to_affine();
+    let separate =
+        || (&g.to_jacobian().scalar_mul(&u) + &point.to_jacobian().scalar_mul(&k)).to_affine();
+    assert_eq!(Point::mul_add(&u, &g, &k, &point), separate());
+    println!(
+        "doublings: separate {}, shamir {}",
+        u.bit_len() + k.bit_len(),
+        u.bit_len().max(k.bit_len())
+    );
+    let two = time("u*G + v*P separate", iterations, || {
+        separate();
+    });
+    let shamir = time("u*G + v*P shamir", iterations, || {
+        Point::mul_add(&u, &g, &k, &point);
+    });
+    println!(
+        "Shamir speedup: {:.1}x",
+        two.as_secs_f64() / shamir.as_secs_f64()
+    );
+
+    assert_eq!(
+        point.mul_glv(&u),
+        point.to_jacobian().scalar_mul(&u).to_affine()
+    );
+    let direct = time("k*P jacobian", iterations, || {
+        point.to_jacobian().scalar_mul(&u).to_affine();
+    });
+    let glv = time("k*P glv", iterations, || {
+        point.mul_glv(&u);
+    });
+    println!(
+        "GLV speedup: {:.1}x",
+        direct.as_secs_f64() / glv.as_secs_f64()
+    );
+
+    time("GeneratorTable::new", 1, || {
+        GeneratorTable::new(&g);
+    });
+    assert_eq!(mul_generator(&k), expected);
+    let ladder = time("k*G ladder", iterations, || {
+        g.scalar_mul_ct(&k);
+    });
+    let table = time("k*G table", iterations, || {
+        mul_generator(&k);
+    });
+    println!(
+        "table speedup: {:.1}x",
+        ladder.as_secs_f64() / table.as_secs_f64()
+    );
+    let key = PrivateKey::new(k.clone()).unwrap();
+    let sign = time("ecdsa sign", iterations, || {
+        key.sign(&u);
+    });
+    println!("signatures per second: {:.0}", 1.0 / sign.as_secs_f64());
+}
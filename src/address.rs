@@ -0,0 +1,139 @@
+use crate::base58;
+use crate::bech32::{decode_segwit, network_hrp};
+use crate::errors::ValueError;
+
+/// The standard output types an address can encode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressType {
+    P2pkh,
+    P2sh,
+    P2wpkh,
+    P2wsh,
+    P2tr,
+}
+
+/// Decodes an address for the given network into its type and payload: the
+/// 20-byte hash for P2PKH, P2SH and P2WPKH, the 32-byte script hash for
+/// P2WSH and the 32-byte output key for P2TR. Bech32 and Bech32m addresses
+/// are recognized by their human-readable part, anything else is treated
+/// as Base58Check.
+pub fn decode_address(addr: &str, testnet: bool) -> Result<(AddressType, Vec<u8>), ValueError> {
+    let lower = addr.to_lowercase();
+    if lower.starts_with("bc1") || lower.starts_with("tb1") {
+        let (version, program) = decode_segwit(network_hrp(testnet), addr)?;
+        let address_type = match (version, program.len()) {
+            (0, 20) => AddressType::P2wpkh,
+            (0, 32) => AddressType::P2wsh,
+            (1, 32) => AddressType::P2tr,
+            _ => {
+                return Err(ValueError {
+                    message: format!(
+                        "unsupported witness v{} program of {} bytes",
+                        version,
+                        program.len()
+                    ),
+                })
+            }
+        };
+        return Ok((address_type, program));
+    }
+    let payload = base58::decode_check(addr)?;
+    if payload.len() != 21 {
+        return Err(ValueError {
+            message: format!("bad address payload length {}", payload.len()),
+        });
+    }
+    let address_type = match (payload[0], testnet) {
+        (0x00, false) | (0x6f, true) => AddressType::P2pkh,
+        (0x05, false) | (0xc4, true) => AddressType::P2sh,
+        (0x00, true) | (0x05, true) => {
+            return Err(ValueError {
+                message: "mainnet address used on testnet".to_string(),
+            })
+        }
+        (0x6f, false) | (0xc4, false) => {
+            return Err(ValueError {
+                message: "testnet address used on mainnet".to_string(),
+            })
+        }
+        (version, _) => {
+            return Err(ValueError {
+                message: format!("unknown address version byte {:#04x}", version),
+            })
+        }
+    };
+    Ok((address_type, payload[1..].to_vec()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hex;
+
+    #[test]
+    fn test_decode_address() {
+        let cases = [
+            (
+                "1BMERxWc9yF8gYAob2FkngUJgnHuB8AUaN",
+                false,
+                AddressType::P2pkh,
+                "7184c9c226be8b92253f539f0b5c0c4016ad9eb0",
+            ),
+            (
+                "3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy",
+                false,
+                AddressType::P2sh,
+                "b472a266d0bd89c13706a4132ccfb16f7c3b9fcb",
+            ),
+            (
+                "2MzQwSSnBHWHqSAqtTVQ6v47XtaisrJa1Vc",
+                true,
+                AddressType::P2sh,
+                "4e9f39ca4688ff102128ea4ccda34105324305b0",
+            ),
+            (
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                false,
+                AddressType::P2wpkh,
+                "751e76e8199196d454941c45d1b3a323f1433bd6",
+            ),
+            (
+                "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+                true,
+                AddressType::P2wpkh,
+                "751e76e8199196d454941c45d1b3a323f1433bd6",
+            ),
+            (
+                "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3",
+                false,
+                AddressType::P2wsh,
+                "1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262",
+            ),
+            (
+                "bc1p2wsldez5mud2yam29q22wgfh9439spgduvct83k3pm50fcxa5dps59h4z5",
+                false,
+                AddressType::P2tr,
+                "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343",
+            ),
+        ];
+        for (addr, testnet, address_type, payload) in cases.iter() {
+            let (got_type, got_payload) = decode_address(addr, *testnet).unwrap();
+            assert_eq!(got_type, *address_type);
+            assert_eq!(hex::encode(&got_payload), *payload);
+        }
+    }
+
+    #[test]
+    fn test_decode_address_rejects() {
+        // right encoding, wrong network
+        assert!(decode_address("1BMERxWc9yF8gYAob2FkngUJgnHuB8AUaN", true).is_err());
+        assert!(decode_address("2MzQwSSnBHWHqSAqtTVQ6v47XtaisrJa1Vc", false).is_err());
+        assert!(decode_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", true).is_err());
+        // corrupted checksum
+        assert!(decode_address("1BMERxWc9yF8gYAob2FkngUJgnHuB8AUaM", false).is_err());
+        // a WIF key is Base58Check but no address
+        assert!(
+            decode_address("5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ", false).is_err()
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Conversions between bitcoin amounts and the satoshi counts that
+//! `TxOut::amount` holds.
+
+use crate::errors::ValueError;
+
+/// Satoshis per bitcoin.
+pub const COIN: u64 = 100_000_000;
+
+/// The 21 million BTC supply cap, in satoshis.
+pub const MAX_MONEY: u64 = 21_000_000 * COIN;
+
+/// Converts a BTC amount to satoshis. The value must be the closest `f64`
+/// to a whole number of satoshis, so `0.1` is accepted, while fractions of
+/// a satoshi, negative or non-finite values and amounts above `MAX_MONEY`
+/// are errors.
+pub fn btc_to_sats(btc: f64) -> Result<u64, ValueError> {
+    if !btc.is_finite() || btc < 0.0 {
+        return Err(ValueError {
+            message: format!("{} BTC is not a valid amount", btc),
+        });
+    }
+    let sats = (btc * COIN as f64).round();
+    if sats > MAX_MONEY as f64 {
+        return Err(ValueError {
+            message: format!("{} BTC exceeds the 21 million BTC supply", btc),
+        });
+    }
+    // below MAX_MONEY < 2^53 every integer is exact, so a value that does
+    // not come back unchanged was not a whole number of satoshis
+    match sats / COIN as f64 == btc {
+        true => Ok(sats as u64),
+        false => Err(ValueError {
+            message: format!("{} BTC is not a whole number of satoshis", btc),
+        }),
+    }
+}
+
+/// Formats satoshis as BTC with all eight decimals, e.g. `0.00010000`.
+/// Integer arithmetic keeps every amount exact.
+pub fn sats_to_btc_string(sats: u64) -> String {
+    format!("{}.{:08}", sats / COIN, sats % COIN)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_btc_to_sats() {
+        assert_eq!(btc_to_sats(0.0).unwrap(), 0);
+        assert_eq!(btc_to_sats(0.00000001).unwrap(), 1);
+        assert_eq!(btc_to_sats(0.1).unwrap(), 10_000_000);
+        // 1.15 * 1e8 is 114999999.99999999 in f64
+        assert_eq!(btc_to_sats(1.15).unwrap(), 115_000_000);
+        assert_eq!(btc_to_sats(6.25).unwrap(), 625_000_000);
+        assert_eq!(btc_to_sats(20_999_999.999_999_99).unwrap(), MAX_MONEY - 1);
+        assert_eq!(btc_to_sats(21_000_000.0).unwrap(), MAX_MONEY);
+    }
+
+    #[test]
+    fn test_btc_to_sats_invalid() {
+        assert_eq!(
+            btc_to_sats(0.000000005).unwrap_err().message,
+            "0.000000005 BTC is not a whole number of satoshis"
+        );
+        assert!(btc_to_sats(1.000000001).is_err());
+        assert_eq!(
+            btc_to_sats(21_000_000.00000001).unwrap_err().message,
+            "21000000.00000001 BTC exceeds the 21 million BTC supply"
+        );
+        assert!(btc_to_sats(1e300).is_err());
+        assert!(btc_to_sats(-1.0).is_err());
+        assert!(btc_to_sats(f64::NAN).is_err());
+        assert!(btc_to_sats(f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn test_sats_to_btc_string() {
+        assert_eq!(sats_to_btc_string(0), "0.00000000");
+        assert_eq!(sats_to_btc_string(1), "0.00000001");
+        assert_eq!(sats_to_btc_string(32_454_049), "0.32454049");
+        assert_eq!(sats_to_btc_string(MAX_MONEY), "21000000.00000000");
+        assert_eq!(sats_to_btc_string(u64::MAX), "184467440737.09551615");
+        for sats in [1, 10_000_000, 115_000_000, MAX_MONEY - 1] {
+            let btc: f64 = sats_to_btc_string(sats).parse().unwrap();
+            assert_eq!(btc_to_sats(btc).unwrap(), sats);
+        }
+    }
+}
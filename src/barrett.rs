@@ -0,0 +1,121 @@
+//! Barrett reduction for a prime that is fixed across many operations.
+
+use crate::ecc::{FieldElement, FieldElementOps};
+use ibig::UBig;
+
+/// Field arithmetic mod a fixed `prime` that replaces the `UBig` division
+/// behind `%` with two multiplications and shifts. The Barrett constant
+/// `mu = floor(4^k / prime)`, where `k` is the bit length of the prime, is
+/// computed once in `new`.
+///
+/// Values are plain residues below the prime; `element` wraps one back up
+/// as a `FieldElement`. Whether this beats `%` depends on the bignum
+/// backend: `ibig` already divides 512-bit by 256-bit values quickly, and
+/// `benches/field_mul.rs` measures the two side by side.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReducingField {
+    prime: UBig,
+    mu: UBig,
+    k: usize,
+}
+
+impl ReducingField {
+    /// Panics if `prime` is below 2.
+    pub fn new(prime: UBig) -> ReducingField {
+        assert!(prime > UBig::from(1u8), "modulus must be at least 2");
+        let k = prime.bit_len();
+        let mu = (UBig::from(1u8) << (2 * k)) / &prime;
+        ReducingField { prime, mu, k }
+    }
+
+    pub fn prime(&self) -> &UBig {
+        &self.prime
+    }
+
+    /// `x` mod the prime. Inputs below `2^(2k)`, which covers any product
+    /// of two residues, take the Barrett path; larger ones fall back to `%`.
+    pub fn reduce(&self, x: &UBig) -> UBig {
+        if x.bit_len() > 2 * self.k {
+            return x % &self.prime;
+        }
+        // q never exceeds floor(x / prime) and falls short by at most 2
+        let q = ((x >> (self.k - 1)) * &self.mu) >> (self.k + 1);
+        let mut r = x - q * &self.prime;
+        while r >= self.prime {
+            r -= &self.prime;
+        }
+        r
+    }
+
+    /// `a + b` for residues `a` and `b`, with at most one subtraction.
+    pub fn add(&self, a: &UBig, b: &UBig) -> UBig {
+        let sum = a + b;
+        match sum >= self.prime {
+            true => sum - &self.prime,
+            false => sum,
+        }
+    }
+
+    /// `a * b` for residues `a` and `b`.
+    pub fn mul(&self, a: &UBig, b: &UBig) -> UBig {
+        self.reduce(&(a * b))
+    }
+
+    /// `num`, reduced, as an element of this field.
+    pub fn element(&self, num: &UBig) -> FieldElement {
+        FieldElement::from_reduced(self.reduce(num), self.prime.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::secp256k1;
+    use ibig::ubig;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_edges() {
+        for prime in [ubig!(2), ubig!(223), secp256k1::p(), secp256k1::n()] {
+            let field = ReducingField::new(prime.clone());
+            let max = &prime - ubig!(1);
+            assert_eq!(field.mul(&max, &max), ubig!(1));
+            assert_eq!(field.add(&max, &max), (&max + &max) % &prime);
+            assert_eq!(field.reduce(&prime), ubig!(0));
+            assert_eq!(field.reduce(&ubig!(0)), ubig!(0));
+            // past 2^(2k) reduce falls back to plain division
+            let big = (&prime << (2 * prime.bit_len())) + ubig!(1);
+            assert_eq!(field.reduce(&big), ubig!(1) % &prime);
+        }
+    }
+
+    #[test]
+    fn test_element() {
+        let field = ReducingField::new(ubig!(223));
+        assert_eq!(
+            field.element(&ubig!(1000)),
+            FieldElement::new(ubig!(108), ubig!(223)).unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must be at least 2")]
+    fn test_modulus_too_small() {
+        ReducingField::new(ubig!(1));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_matches_field_element(a in any::<[u8; 32]>(), b in any::<[u8; 32]>()) {
+            for prime in [secp256k1::p(), secp256k1::n(), ubig!(223)] {
+                let field = ReducingField::new(prime.clone());
+                let x = FieldElement::from_reduced(UBig::from_be_bytes(&a), prime.clone());
+                let y = FieldElement::from_reduced(UBig::from_be_bytes(&b), prime.clone());
+                prop_assert_eq!(field.mul(&x.num, &y.num), (&x * &y).num);
+                prop_assert_eq!(field.add(&x.num, &y.num), (&x + &y).num);
+                let wide = UBig::from_be_bytes(&a) * UBig::from_be_bytes(&b);
+                prop_assert_eq!(field.reduce(&wide), &wide % &prime);
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::errors::ValueError;
+use crate::hash::hash256;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::convert::TryFrom;
+use ibig::{ubig, UBig};
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Base58 encoding with the Bitcoin alphabet. Each leading zero byte becomes
+/// a leading `1`.
+pub fn encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|b| **b == 0).count();
+    let mut num = UBig::from_be_bytes(data);
+    let mut digits = Vec::new();
+    while num > ubig!(0) {
+        let rem = usize::try_from(&num % ubig!(58)).unwrap();
+        digits.push(ALPHABET[rem]);
+        num /= ubig!(58);
+    }
+    digits.extend(core::iter::repeat_n(ALPHABET[0], zeros));
+    digits.iter().rev().map(|d| *d as char).collect()
+}
+
+pub fn decode(s: &str) -> Result<Vec<u8>, ValueError> {
+    let zeros = s.bytes().take_while(|c| *c == ALPHABET[0]).count();
+    let mut num = ubig!(0);
+    for c in s.chars() {
+        let digit = ALPHABET
+            .iter()
+            .position(|a| *a as char == c)
+            .ok_or_else(|| ValueError {
+                message: format!("invalid base58 character {:?}", c),
+            })?;
+        num = num * ubig!(58) + UBig::from(digit);
+    }
+    let mut out = vec![0u8; zeros];
+    if num > ubig!(0) {
+        out.extend(num.to_be_bytes());
+    }
+    Ok(out)
+}
+
+fn checksum(data: &[u8]) -> [u8; 4] {
+    let hash = hash256(data);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Base58 encoding of `data` followed by the first four bytes of its
+/// double SHA-256.
+pub fn encode_check(data: &[u8]) -> String {
+    let mut payload = data.to_vec();
+    payload.extend_from_slice(&checksum(data));
+    encode(&payload)
+}
+
+/// Decodes a Base58Check string and returns the payload without its
+/// checksum.
+pub fn decode_check(s: &str) -> Result<Vec<u8>, ValueError> {
+    let mut payload = decode(s)?;
+    if payload.len() < 4 {
+        return Err(ValueError {
+            message: "base58check string too short for a checksum".to_string(),
+        });
+    }
+    let check = payload.split_off(payload.len() - 4);
+    if check != checksum(&payload) {
+        return Err(ValueError {
+            message: format!("bad base58check checksum for {}", s),
+        });
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hex;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(b""), "");
+        assert_eq!(encode(&[0]), "1");
+        assert_eq!(encode(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(encode(&hex::decode("0000287fb4cd").unwrap()), "11233QC4");
+        assert_eq!(
+            encode(&hex::decode("00eb15231dfceb60925886b67d065299925915aeb172c06647").unwrap()),
+            "1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9L"
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode("").unwrap(), b"");
+        assert_eq!(decode("1").unwrap(), vec![0]);
+        assert_eq!(decode("2NEpo7TZRRrLZSi2U").unwrap(), b"Hello World!");
+        assert_eq!(
+            decode("11233QC4").unwrap(),
+            hex::decode("0000287fb4cd").unwrap()
+        );
+        // 0, O, I and l are excluded from the alphabet
+        assert!(decode("10OIl").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let payload = hex::decode("007680adec8eabcabac676be9e83854ade0bd22cdb").unwrap();
+        assert_eq!(encode_check(&payload), "1BoatSLRHtKNngkdXEeobR76b53LETtpyT");
+        assert_eq!(
+            decode_check("1BoatSLRHtKNngkdXEeobR76b53LETtpyT").unwrap(),
+            payload
+        );
+        assert!(decode_check("1BoatSLRHtKNngkdXEeobR76b53LETtpyU").is_err());
+        assert!(decode_check("1111").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::errors::ValueError;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard padded Base64 (RFC 4648), as used for signed messages.
+pub fn encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, b)| acc | (u32::from(*b) << (16 - 8 * i)));
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => out.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char),
+                false => out.push('='),
+            }
+        }
+    }
+    out
+}
+
+/// Decodes padded Base64, rejecting characters outside the alphabet,
+/// missing or misplaced padding and non-zero bits left over in the padding.
+pub fn decode(s: &str) -> Result<Vec<u8>, ValueError> {
+    let invalid = |reason: &str| ValueError {
+        message: format!("invalid base64: {}", reason),
+    };
+    let bytes = s.as_bytes();
+    if !bytes.len().is_multiple_of(4) {
+        return Err(invalid("length is not a multiple of 4"));
+    }
+    let mut out = Vec::with_capacity(bytes.len() / 4 * 3);
+    for (n, chunk) in bytes.chunks(4).enumerate() {
+        let last = n == bytes.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|c| **c == b'=').count();
+        if padding > 2 || (padding > 0 && !last) {
+            return Err(invalid("misplaced padding"));
+        }
+        let mut group = 0u32;
+        for c in &chunk[..4 - padding] {
+            let digit = ALPHABET
+                .iter()
+                .position(|a| a == c)
+                .ok_or_else(|| invalid(&format!("character {:?}", *c as char)))?;
+            group = (group << 6) | digit as u32;
+        }
+        group <<= 6 * padding;
+        let decoded = [(group >> 16) as u8, (group >> 8) as u8, group as u8];
+        if decoded[3 - padding..].iter().any(|b| *b != 0) {
+            return Err(invalid("non-zero padding bits"));
+        }
+        out.extend_from_slice(&decoded[..3 - padding]);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rfc4648_vectors() {
+        let cases = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (data, encoded) in cases.iter() {
+            assert_eq!(encode(data.as_bytes()), *encoded);
+            assert_eq!(decode(encoded).unwrap(), data.as_bytes());
+        }
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(decode(&encode(&bytes)).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_decode_rejects() {
+        assert!(decode("Zm9").is_err());
+        assert!(decode("Zm9v!A==").is_err());
+        assert!(decode("Zg==Zg==").is_err());
+        assert!(decode("Z===").is_err());
+        // "Zh==" leaves a set bit in the padding
+        assert!(decode("Zh==").is_err());
+    }
+}
@@ -0,0 +1,455 @@
+use crate::errors::ValueError;
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+const BECH32M_CONST: u32 = 0x2bc830a3;
+
+/// Checksum flavour. BIP173 Bech32 is used for witness v0, BIP350 Bech32m
+/// for witness v1 and above.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Variant {
+    fn constant(self) -> u32 {
+        match self {
+            Variant::Bech32 => 1,
+            Variant::Bech32m => BECH32M_CONST,
+        }
+    }
+
+    fn from_residue(residue: u32) -> Option<Variant> {
+        match residue {
+            1 => Some(Variant::Bech32),
+            BECH32M_CONST => Some(Variant::Bech32m),
+            _ => None,
+        }
+    }
+}
+
+fn polymod(values: &[u8]) -> u32 {
+    let mut chk: u32 = 1;
+    for v in values {
+        let top = chk >> 25;
+        chk = (chk & 0x1ffffff) << 5 ^ u32::from(*v);
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut out: Vec<u8> = hrp.bytes().map(|c| c >> 5).collect();
+    out.push(0);
+    out.extend(hrp.bytes().map(|c| c & 31));
+    out
+}
+
+fn create_checksum(hrp: &str, data: &[u8], variant: Variant) -> [u8; 6] {
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(data);
+    values.extend_from_slice(&[0; 6]);
+    let pm = polymod(&values) ^ variant.constant();
+    let mut out = [0u8; 6];
+    for (i, c) in out.iter_mut().enumerate() {
+        *c = ((pm >> (5 * (5 - i))) & 31) as u8;
+    }
+    out
+}
+
+/// Encodes 5-bit `data` under the human-readable part `hrp`, appending the
+/// six-character BCH checksum of the given `variant`.
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> String {
+    let hrp = hrp.to_lowercase();
+    let mut out = hrp.clone();
+    out.push('1');
+    for d in data
+        .iter()
+        .chain(create_checksum(&hrp, data, variant).iter())
+    {
+        out.push(CHARSET[*d as usize] as char);
+    }
+    out
+}
+
+/// Decodes a Bech32 or Bech32m string into its human-readable part and 5-bit
+/// data, with the checksum verified and stripped. The variant is detected
+/// from the checksum.
+pub fn decode(s: &str) -> Result<(String, Vec<u8>, Variant), ValueError> {
+    let invalid = |reason: &str| ValueError {
+        message: format!("invalid bech32 string: {}", reason),
+    };
+    if s.len() > 90 {
+        return Err(invalid("longer than 90 characters"));
+    }
+    if s.bytes().any(|c| !(33..=126).contains(&c)) {
+        return Err(invalid("character out of range"));
+    }
+    if s.bytes().any(|c| c.is_ascii_lowercase()) && s.bytes().any(|c| c.is_ascii_uppercase()) {
+        return Err(invalid("mixed case"));
+    }
+    let s = s.to_lowercase();
+    let pos = match s.rfind('1') {
+        Some(pos) if pos >= 1 => pos,
+        _ => return Err(invalid("missing separator or empty human-readable part")),
+    };
+    if pos + 7 > s.len() {
+        return Err(invalid("checksum too short"));
+    }
+    let hrp = &s[..pos];
+    let mut data = Vec::with_capacity(s.len() - pos - 1);
+    for c in s[pos + 1..].bytes() {
+        match CHARSET.iter().position(|d| *d == c) {
+            Some(v) => data.push(v as u8),
+            None => return Err(invalid("invalid data character")),
+        }
+    }
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(&data);
+    let variant = match Variant::from_residue(polymod(&values)) {
+        Some(variant) => variant,
+        None => return Err(invalid("bad checksum")),
+    };
+    data.truncate(data.len() - 6);
+    Ok((hrp.to_string(), data, variant))
+}
+
+/// Regroups `data` from `from`-bit to `to`-bit values. With `pad` a final
+/// partial group is zero-padded; without it, leftover bits must be zero
+/// padding of less than `from` bits.
+pub fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, ValueError> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max = (1u32 << to) - 1;
+    let mut out = Vec::new();
+    for value in data {
+        let value = u32::from(*value);
+        if value >> from != 0 {
+            return Err(ValueError {
+                message: format!("value {} does not fit in {} bits", value, from),
+            });
+        }
+        acc = (acc << from) | value;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || (acc << (to - bits)) & max != 0 {
+        return Err(ValueError {
+            message: "invalid padding in bit conversion".to_string(),
+        });
+    }
+    Ok(out)
+}
+
+/// Encodes a SegWit address for witness `version` and `program`, choosing
+/// Bech32 for v0 and Bech32m for later versions.
+pub fn encode_segwit(hrp: &str, version: u8, program: &[u8]) -> Result<String, ValueError> {
+    check_program(version, program)?;
+    let variant = match version {
+        0 => Variant::Bech32,
+        _ => Variant::Bech32m,
+    };
+    let mut data = vec![version];
+    data.extend(convert_bits(program, 8, 5, true)?);
+    Ok(encode(hrp, &data, variant))
+}
+
+/// Decodes a SegWit address expected to carry `hrp`, returning its witness
+/// version and program. A v0 address must use Bech32 and any later version
+/// Bech32m.
+pub fn decode_segwit(hrp: &str, addr: &str) -> Result<(u8, Vec<u8>), ValueError> {
+    let (got, data, variant) = decode(addr)?;
+    if got != hrp {
+        return Err(ValueError {
+            message: format!("expected human-readable part {}, got {}", hrp, got),
+        });
+    }
+    let (version, program) = match data.split_first() {
+        Some((version, rest)) => (*version, convert_bits(rest, 5, 8, false)?),
+        None => {
+            return Err(ValueError {
+                message: "segwit address has no witness version".to_string(),
+            })
+        }
+    };
+    check_program(version, &program)?;
+    let expected = match version {
+        0 => Variant::Bech32,
+        _ => Variant::Bech32m,
+    };
+    if variant != expected {
+        return Err(ValueError {
+            message: format!(
+                "witness v{} address must use {:?}, not {:?}",
+                version, expected, variant
+            ),
+        });
+    }
+    Ok((version, program))
+}
+
+fn check_program(version: u8, program: &[u8]) -> Result<(), ValueError> {
+    if version > 16 {
+        return Err(ValueError {
+            message: format!("invalid witness version {}", version),
+        });
+    }
+    if program.len() < 2 || program.len() > 40 {
+        return Err(ValueError {
+            message: format!("invalid witness program length {}", program.len()),
+        });
+    }
+    if version == 0 && program.len() != 20 && program.len() != 32 {
+        return Err(ValueError {
+            message: format!("invalid witness v0 program length {}", program.len()),
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn network_hrp(testnet: bool) -> &'static str {
+    match testnet {
+        true => "tb",
+        false => "bc",
+    }
+}
+
+/// Native SegWit v0 pay-to-witness-pubkey-hash address for `h160`.
+pub fn p2wpkh_address(h160: &[u8; 20], testnet: bool) -> String {
+    encode_segwit(network_hrp(testnet), 0, h160).unwrap()
+}
+
+/// Taproot (witness v1) address for a 32-byte x-only output key.
+pub fn p2tr_address(x_only_pubkey: &[u8; 32], testnet: bool) -> String {
+    encode_segwit(network_hrp(testnet), 1, x_only_pubkey).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hex;
+
+    #[test]
+    fn test_valid() {
+        let valid = [
+            "A12UEL5L",
+            "a12uel5l",
+            "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+            "11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j",
+            "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+            "?1ezyfcl",
+        ];
+        for s in valid.iter() {
+            let (hrp, data, variant) = decode(s).unwrap();
+            assert_eq!(variant, Variant::Bech32);
+            assert_eq!(encode(&hrp, &data, variant), s.to_lowercase());
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        let invalid = [
+            "\u{20}1nwldj5",
+            "\u{7f}1axkwrx",
+            "\u{80}1eym55h",
+            "an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx",
+            "pzry9x0s0muk",
+            "1pzry9x0s0muk",
+            "x1b4n0q5v",
+            "li1dgmt3",
+            "de1lg7wt\u{ff}",
+            "A1G7SGD8",
+            "10a06t8",
+            "1qzzfhee",
+        ];
+        for s in invalid.iter() {
+            assert!(decode(s).is_err(), "{:?} should not decode", s);
+        }
+    }
+
+    #[test]
+    fn test_convert_bits() {
+        let bytes = [0xff, 0x00, 0xab];
+        let five = convert_bits(&bytes, 8, 5, true).unwrap();
+        assert!(five.iter().all(|v| *v < 32));
+        assert_eq!(convert_bits(&five, 5, 8, false).unwrap(), bytes);
+        assert!(convert_bits(&[32], 5, 8, true).is_err());
+        // a lone 5-bit group cannot hold a full byte
+        assert!(convert_bits(&[1], 5, 8, false).is_err());
+    }
+
+    #[test]
+    fn test_p2wpkh_address() {
+        let h160 = [
+            0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94, 0x1c, 0x45, 0xd1, 0xb3,
+            0xa3, 0x23, 0xf1, 0x43, 0x3b, 0xd6,
+        ];
+        assert_eq!(
+            p2wpkh_address(&h160, false),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+        assert_eq!(
+            p2wpkh_address(&h160, true),
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"
+        );
+        let (version, program) =
+            decode_segwit("bc", "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4").unwrap();
+        assert_eq!(version, 0);
+        assert_eq!(program, h160);
+    }
+
+    #[test]
+    fn test_valid_bech32m() {
+        let valid = [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11sg7hg6",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            "11llllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllludsr8",
+            "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+            "?1v759aa",
+        ];
+        for s in valid.iter() {
+            let (hrp, data, variant) = decode(s).unwrap();
+            assert_eq!(variant, Variant::Bech32m);
+            assert_eq!(encode(&hrp, &data, variant), s.to_lowercase());
+        }
+    }
+
+    #[test]
+    fn test_invalid_bech32m() {
+        let invalid = [
+            "\u{20}1xj0phk",
+            "\u{7f}1g6xzxy",
+            "\u{80}1vctc34",
+            "an84characterslonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11d6pts4",
+            "qyrz8wqd2c9m",
+            "1qyrz8wqd2c9m",
+            "y1b0jsk6g",
+            "lt1igcx5c0",
+            "in1muywd",
+            "mm1crxm3i",
+            "au1s5cgom",
+            "M1VUXWEZ",
+            "16plkw9",
+            "1p2gdwpf",
+        ];
+        for s in invalid.iter() {
+            assert!(decode(s).is_err(), "{:?} should not decode", s);
+        }
+    }
+
+    #[test]
+    fn test_valid_segwit_addresses() {
+        let valid = [
+            (
+                "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4",
+                "0014751e76e8199196d454941c45d1b3a323f1433bd6",
+            ),
+            (
+                "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7",
+                "00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262",
+            ),
+            (
+                "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y",
+                "5128751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6",
+            ),
+            ("BC1SW50QGDZ25J", "6002751e"),
+            (
+                "bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs",
+                "5210751e76e8199196d454941c45d1b3a323",
+            ),
+            (
+                "tb1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesrxh6hy",
+                "0020000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433",
+            ),
+            (
+                "tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c",
+                "5120000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433",
+            ),
+            (
+                "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+                "512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            ),
+        ];
+        for (addr, script) in valid.iter() {
+            let hrp = addr[..2].to_lowercase();
+            let (version, program) = decode_segwit(&hrp, addr).unwrap();
+            let script = hex::decode(script).unwrap();
+            let op = if version == 0 { 0 } else { version + 0x50 };
+            assert_eq!(script[0], op);
+            assert_eq!(script[2..], program[..]);
+            assert_eq!(
+                encode_segwit(&hrp, version, &program).unwrap(),
+                addr.to_lowercase()
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_segwit_addresses() {
+        let invalid = [
+            // invalid human-readable part
+            "tc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq5zuyut",
+            // Bech32 checksum on v1+
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd",
+            "tb1z0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqglt7rf",
+            "BC1S0XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ54WELL",
+            // Bech32m checksum on v0
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh",
+            "tb1q0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq24jc47",
+            "bc1p38j9r5y49hruaue7wxjce0updqjuyyx0kh56v8s25huc6995vvpql3jow4",
+            "BC130XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ7ZWS8R",
+            "bc1pw5dgrnzv",
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v8n0nx0muaewav253zgeav",
+            "BC1QR508D6QEJXTDG4Y5R3ZARVARYV98GJ9P",
+            "tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq47Zagq",
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v07qwwzcrf",
+            "tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vpggkg4j",
+            "bc1gmk9yu",
+        ];
+        for addr in invalid.iter() {
+            assert!(
+                decode_segwit("bc", addr).is_err(),
+                "{:?} should not decode",
+                addr
+            );
+            assert!(
+                decode_segwit("tb", addr).is_err(),
+                "{:?} should not decode",
+                addr
+            );
+        }
+    }
+
+    #[test]
+    fn test_p2tr_address() {
+        let x_only =
+            hex::decode("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                .unwrap();
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&x_only);
+        assert_eq!(
+            p2tr_address(&key, false),
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0"
+        );
+        assert_eq!(
+            decode_segwit("bc", &p2tr_address(&key, false)).unwrap(),
+            (1, x_only)
+        );
+        assert!(p2tr_address(&key, true).starts_with("tb1p"));
+    }
+}
@@ -0,0 +1,195 @@
+//! Keyed constructions built on the hash functions of `hash`.
+
+use crate::hash::{sha256, sha512};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// HMAC (RFC 2104) over SHA-256.
+pub fn hmac_sha256(key: &[u8], msg: &[u8]) -> [u8; 32] {
+    let mut block_key = [0u8; 64];
+    if key.len() > 64 {
+        block_key[..32].copy_from_slice(&sha256(key));
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+    let mut inner: Vec<u8> = block_key.iter().map(|b| b ^ 0x36).collect();
+    inner.extend_from_slice(msg);
+    let mut outer: Vec<u8> = block_key.iter().map(|b| b ^ 0x5c).collect();
+    outer.extend_from_slice(&sha256(&inner));
+    sha256(&outer)
+}
+
+/// HMAC (RFC 2104) over SHA-512.
+pub fn hmac_sha512(key: &[u8], msg: &[u8]) -> [u8; 64] {
+    let mut block_key = [0u8; 128];
+    if key.len() > 128 {
+        block_key[..64].copy_from_slice(&sha512(key));
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+    let mut inner: Vec<u8> = block_key.iter().map(|b| b ^ 0x36).collect();
+    inner.extend_from_slice(msg);
+    let mut outer: Vec<u8> = block_key.iter().map(|b| b ^ 0x5c).collect();
+    outer.extend_from_slice(&sha512(&inner));
+    sha512(&outer)
+}
+
+/// PBKDF2 (RFC 8018) with HMAC-SHA512, stretched to `out_len` bytes. Each
+/// 64-byte block `i` (from 1) XORs together `iterations` chained HMACs,
+/// the first over `salt || i`; the last block is truncated.
+///
+/// Panics if `iterations` is zero.
+pub fn pbkdf2_hmac_sha512(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    out_len: usize,
+) -> Vec<u8> {
+    assert!(iterations > 0, "PBKDF2 needs at least one iteration");
+    let mut out = Vec::with_capacity(out_len);
+    let mut index = 1u32;
+    while out.len() < out_len {
+        let mut block = salt.to_vec();
+        block.extend_from_slice(&index.to_be_bytes());
+        let mut u = hmac_sha512(password, &block);
+        let mut t = u;
+        for _ in 1..iterations {
+            u = hmac_sha512(password, &u);
+            for (t, b) in t.iter_mut().zip(u.iter()) {
+                *t ^= b;
+            }
+        }
+        let take = (out_len - out.len()).min(t.len());
+        out.extend_from_slice(&t[..take]);
+        index += 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hex;
+
+    // RFC 4231 test cases 1 to 4, 6 and 7 (case 5 checks truncated output)
+    fn rfc4231_cases() -> Vec<(Vec<u8>, Vec<u8>, &'static str, &'static str)> {
+        vec![
+            (
+                vec![0x0b; 20],
+                b"Hi There".to_vec(),
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+                "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+            ),
+            (
+                b"Jefe".to_vec(),
+                b"what do ya want for nothing?".to_vec(),
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+                "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+            ),
+            (
+                vec![0xaa; 20],
+                vec![0xdd; 50],
+                "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+                "fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb",
+            ),
+            (
+                (1..=25).collect(),
+                vec![0xcd; 50],
+                "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+                "b0ba465637458c6990e5a8c5f61d4af7e576d97ff94b872de76f8050361ee3dba91ca5c11aa25eb4d679275cc5788063a5f19741120c4f2de2adebeb10a298dd",
+            ),
+            (
+                vec![0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(),
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+                "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+            ),
+            (
+                vec![0xaa; 131],
+                b"This is a test using a larger than block-size key and a larger than block-size data. The key needs to be hashed before being used by the HMAC algorithm.".to_vec(),
+                "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+                "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58",
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        for (key, msg, expected, _) in rfc4231_cases() {
+            assert_eq!(hex::encode(&hmac_sha256(&key, &msg)), expected);
+        }
+        assert_eq!(
+            hex::encode(&hmac_sha256(
+                b"key",
+                b"The quick brown fox jumps over the lazy dog"
+            )),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[test]
+    fn test_hmac_sha512() {
+        for (key, msg, _, expected) in rfc4231_cases() {
+            assert_eq!(hex::encode(&hmac_sha512(&key, &msg)), expected);
+        }
+        // keys longer than a block are hashed first
+        assert_eq!(
+            hex::encode(&hmac_sha512(&[b'k'; 200], b"msg")),
+            "b5245971beb52a5a986812c4666a05c735bf5bb7aba32eae2192adad605df4112d6c285d1c46cf81ccb7ab8c2c3b7b3c6793216909b5add05223ed21f24cdb1e"
+        );
+    }
+
+    #[test]
+    fn test_pbkdf2_hmac_sha512() {
+        assert_eq!(
+            hex::encode(&pbkdf2_hmac_sha512(b"password", b"salt", 1, 64)),
+            "867f70cf1ade02cff3752599a3a53dc4af34c7a669815ae5d513554e1c8cf252c02d470a285a0501bad999bfe943c08f050235d7d68b1da55e63f73b60a57fce"
+        );
+        assert_eq!(
+            hex::encode(&pbkdf2_hmac_sha512(b"password", b"salt", 2, 64)),
+            "e1d9c16aa681708a45f5c7c4e215ceb66e011a2e9f0040713f18aefdb866d53cf76cab2868a39b9f7840edce4fef5a82be67335c77a6068e04112754f27ccf4e"
+        );
+        assert_eq!(
+            hex::encode(&pbkdf2_hmac_sha512(
+                b"passwordPASSWORDpassword",
+                b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+                4096,
+                64
+            )),
+            "8c0511f4c6e597c6ac6315d8f0362e225f3c501495ba23b868c005174dc4ee71115b59f9e60cd9532fa33e0f75aefe30225c583a186cd82bd4daea9724a3d3b8"
+        );
+    }
+
+    #[test]
+    fn test_pbkdf2_hmac_sha512_lengths() {
+        let full = pbkdf2_hmac_sha512(b"password", b"salt", 1, 100);
+        assert_eq!(
+            hex::encode(&full[64..]),
+            "7b532e206c2967d4c7d2ffa460539fc4d4e5eec70125d74c6c7cf86d25284f297907fcea"
+        );
+        assert_eq!(pbkdf2_hmac_sha512(b"password", b"salt", 1, 20), &full[..20]);
+        assert!(pbkdf2_hmac_sha512(b"password", b"salt", 1, 0).is_empty());
+    }
+
+    #[test]
+    fn test_pbkdf2_bip39_seed() {
+        // BIP39's 2048 rounds, for the all-"abandon" mnemonic and "TREZOR"
+        let mut words = vec!["abandon"; 11];
+        words.push("about");
+        assert_eq!(
+            hex::encode(&pbkdf2_hmac_sha512(
+                words.join(" ").as_bytes(),
+                b"mnemonicTREZOR",
+                2048,
+                64
+            )),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "PBKDF2 needs at least one iteration")]
+    fn test_pbkdf2_zero_iterations() {
+        pbkdf2_hmac_sha512(b"password", b"salt", 0, 64);
+    }
+}
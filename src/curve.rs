@@ -0,0 +1,161 @@
+use crate::ecc::{FieldElement, FieldElementOps};
+use crate::errors::EccError;
+use crate::point::{is_on_curve, Point, PointOps};
+use crate::secp256k1;
+use ibig::UBig;
+
+/// Parameters of a short Weierstrass curve `y^2 = x^3 + a*x + b` over the
+/// field of prime `p`, with generator `g` of order `n`. Points carry their
+/// own `a` and `b`, so all point arithmetic already works on any such
+/// curve; a `Curve` bundles the rest and creates and checks points.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Curve {
+    pub a: FieldElement,
+    pub b: FieldElement,
+    pub g: Point,
+    pub n: UBig,
+    pub p: UBig,
+}
+
+impl Curve {
+    /// Builds a curve from raw integers, checking the coefficients are field
+    /// elements and `(gx, gy)` lies on the curve. The order `n` is taken on
+    /// trust.
+    pub fn new(p: UBig, a: UBig, b: UBig, gx: UBig, gy: UBig, n: UBig) -> Result<Curve, EccError> {
+        let a = FieldElement::new(a, p.clone())?;
+        let b = FieldElement::new(b, p.clone())?;
+        let g = Point::new(
+            Some(FieldElement::new(gx, p.clone())?),
+            Some(FieldElement::new(gy, p.clone())?),
+            a.clone(),
+            b.clone(),
+        )?;
+        Ok(Curve { a, b, g, n, p })
+    }
+
+    pub fn secp256k1() -> Curve {
+        Curve {
+            a: secp256k1::a(),
+            b: secp256k1::b(),
+            g: secp256k1::g(),
+            n: secp256k1::n(),
+            p: secp256k1::p(),
+        }
+    }
+
+    /// Creates the point `(x, y)`, checking it lies on this curve.
+    pub fn point(&self, x: UBig, y: UBig) -> Result<Point, EccError> {
+        Point::new(
+            Some(FieldElement::new(x, self.p.clone())?),
+            Some(FieldElement::new(y, self.p.clone())?),
+            self.a.clone(),
+            self.b.clone(),
+        )
+    }
+
+    pub fn infinity(&self) -> Point {
+        Point::infinity(self.a.clone(), self.b.clone())
+    }
+
+    /// Whether `point` belongs to this curve: it carries this curve's
+    /// coefficients and is either infinity or satisfies the equation.
+    pub fn contains(&self, point: &Point) -> bool {
+        if point.a != self.a || point.b != self.b {
+            return false;
+        }
+        match (&point.x, &point.y) {
+            (Some(x), Some(y)) => x.prime == self.p && is_on_curve(x, y, &self.a, &self.b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ibig::ubig;
+
+    fn hex(s: &str) -> UBig {
+        UBig::from_str_radix(s, 16).unwrap()
+    }
+
+    fn small_curve() -> Curve {
+        // y^2 = x^3 + 7 over F_223, where (47, 71) has order 21
+        Curve::new(
+            ubig!(223),
+            ubig!(0),
+            ubig!(7),
+            ubig!(47),
+            ubig!(71),
+            ubig!(21),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_independent_curves() {
+        let small = small_curve();
+        let secp = Curve::secp256k1();
+        assert!(small.contains(&small.g));
+        assert!(secp.contains(&secp.g));
+        assert!(!small.contains(&secp.g));
+        assert!(!secp.contains(&small.g));
+        assert!(small.contains(&small.point(ubig!(17), ubig!(56)).unwrap()));
+        assert!(small.point(ubig!(17), ubig!(57)).is_err());
+        assert!(small.contains(&small.infinity()));
+        assert!(!secp.contains(&small.infinity()));
+        for curve in [small, secp].iter() {
+            assert!(curve.g.scalar_mul(&curve.n).is_infinity());
+            assert_eq!(curve.g.scalar_mul(&(&curve.n + ubig!(1))), curve.g);
+        }
+    }
+
+    #[test]
+    fn test_secp256r1() {
+        let p = hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff");
+        let curve = Curve::new(
+            p.clone(),
+            &p - ubig!(3),
+            hex("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b"),
+            hex("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"),
+            hex("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"),
+            hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551"),
+        )
+        .unwrap();
+        let two_g = curve
+            .point(
+                hex("7cf27b188d034f7e8a52380304b51ac3c08969e277f21b35a60b48fc47669978"),
+                hex("07775510db8ed040293d9ac69f7430dbba7dade63ce982299e04b79d227873d1"),
+            )
+            .unwrap();
+        assert_eq!(&curve.g + &curve.g, two_g);
+        assert!(curve.g.to_jacobian().scalar_mul(&curve.n).is_infinity());
+    }
+
+    #[test]
+    fn test_new_rejects() {
+        // generator off the curve
+        assert_eq!(
+            Curve::new(
+                ubig!(223),
+                ubig!(0),
+                ubig!(7),
+                ubig!(47),
+                ubig!(72),
+                ubig!(21)
+            ),
+            Err(EccError::NotOnCurve)
+        );
+        // coefficient outside the field
+        assert!(Curve::new(
+            ubig!(223),
+            ubig!(223),
+            ubig!(7),
+            ubig!(47),
+            ubig!(71),
+            ubig!(21)
+        )
+        .is_err());
+    }
+}
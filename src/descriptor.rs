@@ -0,0 +1,248 @@
+//! A minimal reader for output descriptors (BIP380) covering the single-key
+//! forms `pkh(KEY)`, `wpkh(KEY)`, `sh(wpkh(KEY))` and `tr(KEY)`, with keys
+//! as hex. Extended keys, derivation paths, script trees and checksums are
+//! not supported.
+
+use crate::bech32::{p2tr_address, p2wpkh_address};
+use crate::errors::ValueError;
+use crate::hash::hash160;
+use crate::hex;
+use crate::point::{sec_is_compressed, Point, PointOps};
+use crate::schnorr::{lift_x, x_bytes};
+use crate::script::{p2pkh_script, p2sh_script, p2tr_script, p2wpkh_script, Script};
+use crate::taproot::tweak_pubkey;
+use ibig::UBig;
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed single-key output descriptor.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Descriptor {
+    /// `pkh(KEY)`: P2PKH, keeping the compression the key was written with.
+    Pkh { key: Point, compressed: bool },
+    /// `wpkh(KEY)`: native P2WPKH of a compressed key.
+    Wpkh(Point),
+    /// `sh(wpkh(KEY))`: P2WPKH nested in P2SH.
+    ShWpkh(Point),
+    /// `tr(KEY)`: a key-path-only Taproot output for an x-only internal key,
+    /// which must be the x coordinate of a curve point.
+    Tr([u8; 32]),
+}
+
+/// Splits `name(argument)` into its name and argument.
+fn split_call(s: &str) -> Option<(&str, &str)> {
+    let open = s.find('(')?;
+    let argument = s[open + 1..].strip_suffix(')')?;
+    Some((&s[..open], argument))
+}
+
+/// A hex SEC key and whether it is compressed.
+fn parse_key(arg: &str) -> Result<(Point, bool), String> {
+    let sec = hex::decode(arg).map_err(|e| e.message)?;
+    let compressed =
+        sec_is_compressed(&sec).ok_or_else(|| format!("{:?} is not a SEC public key", arg))?;
+    let key = Point::parse(&sec).map_err(|e| e.message)?;
+    Ok((key, compressed))
+}
+
+fn parse_compressed_key(arg: &str) -> Result<Point, String> {
+    match parse_key(arg)? {
+        (key, true) => Ok(key),
+        (_, false) => Err("segwit keys must be compressed".to_string()),
+    }
+}
+
+/// A 32-byte x-only key, or a compressed key whose prefix is dropped.
+fn parse_x_only_key(arg: &str) -> Result<[u8; 32], String> {
+    let bytes = hex::decode(arg).map_err(|e| e.message)?;
+    let x = match (bytes.len(), sec_is_compressed(&bytes)) {
+        (32, _) => &bytes[..],
+        (33, Some(true)) => &bytes[1..],
+        _ => return Err(format!("{:?} is not an x-only public key", arg)),
+    };
+    let point =
+        lift_x(&UBig::from_be_bytes(x)).ok_or_else(|| format!("{:?} is not on the curve", arg))?;
+    Ok(x_bytes(&point))
+}
+
+impl FromStr for Descriptor {
+    type Err = ValueError;
+    fn from_str(s: &str) -> Result<Descriptor, ValueError> {
+        let invalid = |reason: String| ValueError {
+            message: format!("invalid descriptor {:?}: {}", s, reason),
+        };
+        if s.contains('#') {
+            return Err(invalid("checksums are not supported".to_string()));
+        }
+        let (name, arg) =
+            split_call(s).ok_or_else(|| invalid("expected FUNCTION(ARGUMENT)".to_string()))?;
+        match name {
+            "pkh" => {
+                let (key, compressed) = parse_key(arg).map_err(invalid)?;
+                Ok(Descriptor::Pkh { key, compressed })
+            }
+            "wpkh" => Ok(Descriptor::Wpkh(
+                parse_compressed_key(arg).map_err(invalid)?,
+            )),
+            "sh" => match split_call(arg) {
+                Some(("wpkh", key)) => Ok(Descriptor::ShWpkh(
+                    parse_compressed_key(key).map_err(invalid)?,
+                )),
+                _ => Err(invalid("only sh(wpkh(KEY)) is supported".to_string())),
+            },
+            "tr" => Ok(Descriptor::Tr(parse_x_only_key(arg).map_err(invalid)?)),
+            _ => Err(invalid(format!("unknown function {:?}", name))),
+        }
+    }
+}
+
+const INFINITE_KEY: &str = "descriptor key is the point at infinity";
+
+/// The key's SEC encoding. Parsed keys are never the point at infinity.
+fn sec(key: &Point, compressed: bool) -> Vec<u8> {
+    key.sec(compressed).expect(INFINITE_KEY)
+}
+
+impl Descriptor {
+    /// The scriptPubKey of the output.
+    ///
+    /// Panics if a key is the point at infinity, or if a `Tr` key is not
+    /// the x coordinate of a curve point; `from_str` produces neither.
+    pub fn script_pubkey(&self) -> Script {
+        match self {
+            Descriptor::Pkh { key, compressed } => p2pkh_script(&hash160(&sec(key, *compressed))),
+            Descriptor::Wpkh(key) => p2wpkh_script(&hash160(&sec(key, true))),
+            Descriptor::ShWpkh(key) => {
+                let redeem = p2wpkh_script(&hash160(&sec(key, true)));
+                p2sh_script(&hash160(&redeem.raw_serialize()))
+            }
+            Descriptor::Tr(internal_key) => p2tr_script(&tweak_pubkey(internal_key, None).0),
+        }
+    }
+
+    /// The address of the output; panics like `script_pubkey`.
+    pub fn address(&self, testnet: bool) -> String {
+        match self {
+            Descriptor::Pkh { key, compressed } => {
+                key.address(*compressed, testnet).expect(INFINITE_KEY)
+            }
+            Descriptor::Wpkh(key) => p2wpkh_address(&hash160(&sec(key, true)), testnet),
+            Descriptor::ShWpkh(key) => {
+                p2wpkh_script(&hash160(&sec(key, true))).p2sh_address(testnet)
+            }
+            Descriptor::Tr(internal_key) => {
+                p2tr_address(&tweak_pubkey(internal_key, None).0, testnet)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Descriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Descriptor::Pkh { key, compressed } => {
+                write!(f, "pkh({})", hex::encode(&sec(key, *compressed)))
+            }
+            Descriptor::Wpkh(key) => write!(f, "wpkh({})", hex::encode(&sec(key, true))),
+            Descriptor::ShWpkh(key) => write!(f, "sh(wpkh({}))", hex::encode(&sec(key, true))),
+            Descriptor::Tr(internal_key) => write!(f, "tr({})", hex::encode(internal_key)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_descriptors() {
+        // the single-key examples of BIP381, BIP382 and BIP386
+        let cases = [
+            (
+                "pkh(02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5)",
+                "76a91406afd46bcdfd22ef94ac122aa11f241244a37ecc88ac",
+                "1cMh228HTCiwS8ZsaakH8A8wze1JR5ZsP",
+            ),
+            (
+                "pkh(04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea235)",
+                "76a914b5bd079c4d57cc7fc28ecf8213a6b791625b818388ac",
+                "1HZwkjkeaoZfTSaJxDw6aKkxp45agDiEzN",
+            ),
+            (
+                "wpkh(02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9)",
+                "00147dd65592d0ab2fe0d0257d571abf032cd9db93dc",
+                "bc1q0ht9tyks4vh7p5p904t340cr9nvahy7u3re7zg",
+            ),
+            (
+                "sh(wpkh(03fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a1460297556))",
+                "a914cc6ffbc0bf31af759451068f90ba7a0272b6b33287",
+                "3LKyvRN6SmYXGBNn8fcQvYxW9MGKtwcinN",
+            ),
+            (
+                "tr(a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd)",
+                "512077aab6e066f8a7419c5ab714c12c67d25007ed55a43cadcacb4d7a970a093f11",
+                "bc1pw74tdcrxlzn5r8z6ku2vztr86fgq0m245s72mjktf4afwzsf8ugs0gs8zu",
+            ),
+        ];
+        for (descriptor, script, address) in cases.iter() {
+            let parsed: Descriptor = descriptor.parse().unwrap();
+            assert_eq!(
+                hex::encode(&parsed.script_pubkey().raw_serialize()),
+                *script
+            );
+            assert_eq!(parsed.address(false), *address);
+            assert_eq!(parsed.to_string(), *descriptor);
+        }
+    }
+
+    #[test]
+    fn test_testnet_and_tr_compressed_key() {
+        let wpkh: Descriptor =
+            "wpkh(02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9)"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            wpkh.address(true),
+            "tb1q0ht9tyks4vh7p5p904t340cr9nvahy7um9zdem"
+        );
+        // BIP386 also accepts a compressed key, dropping its prefix
+        let tr: Descriptor =
+            "tr(03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd)"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            tr.to_string(),
+            "tr(a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd)"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "internal key is not on the curve")]
+    fn test_tr_off_curve() {
+        let mut x = [0u8; 32];
+        x[31] = 5;
+        Descriptor::Tr(x).script_pubkey();
+    }
+
+    #[test]
+    fn test_invalid() {
+        let key = "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+        let error = |s: &str| s.parse::<Descriptor>().unwrap_err().message;
+        assert_eq!(
+            error(&format!("multi({})", key)),
+            format!(
+                "invalid descriptor \"multi({})\": unknown function \"multi\"",
+                key
+            )
+        );
+        assert!(error(&format!("sh(pkh({}))", key)).ends_with("only sh(wpkh(KEY)) is supported"));
+        assert!(error(&format!("wpkh({})#8zl0zxma", key)).ends_with("checksums are not supported"));
+        assert!(error(&format!("wpkh({}", key)).ends_with("expected FUNCTION(ARGUMENT)"));
+        assert!(error("wpkh(04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea235)")
+            .ends_with("segwit keys must be compressed"));
+        assert!(error("pkh(02ff)").ends_with("\"02ff\" is not a SEC public key"));
+        assert!(error("pkh(zz)").contains("invalid descriptor"));
+        // x = 5 has no point on the curve
+        assert!(error(&format!("tr({:064x})", 5)).ends_with("is not on the curve"));
+    }
+}
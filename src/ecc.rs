@@ -1,26 +1,151 @@
-use crate::errors::ValueError;
-use ibig::{ubig, UBig};
-use std::cmp::PartialEq;
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use crate::errors::{EccError, ValueError};
+use crate::hex;
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::cmp::{Ordering, PartialEq};
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{Product, Sum};
+use core::mem;
+use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+use ibig::ops::RemEuclid;
+use ibig::{ibig, ubig, IBig, UBig};
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawFieldElement"))]
 pub struct FieldElement {
+    #[cfg_attr(feature = "serde", serde(with = "ubig_hex"))]
     pub num: UBig,
+    #[cfg_attr(feature = "serde", serde(with = "ubig_hex"))]
     pub prime: UBig,
 }
 
-pub trait FieldElementOps {
-    fn new(num: UBig, prime: UBig) -> Result<FieldElement, ValueError>;
+/// The unchecked wire form of `FieldElement`; deserializing goes through
+/// `FieldElement::new` so that `num` is always below `prime`.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawFieldElement {
+    #[serde(with = "ubig_hex")]
+    num: UBig,
+    #[serde(with = "ubig_hex")]
+    prime: UBig,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawFieldElement> for FieldElement {
+    type Error = EccError;
+    fn try_from(raw: RawFieldElement) -> Result<FieldElement, EccError> {
+        FieldElement::new(raw.num, raw.prime)
+    }
+}
+
+/// Serializes `UBig` fields as lowercase hex strings.
+#[cfg(feature = "serde")]
+mod ubig_hex {
+    #[cfg(not(feature = "std"))]
+    use alloc::{format, string::String};
+    use ibig::UBig;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &UBig, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:x}", value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<UBig, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        UBig::from_str_radix(&s, 16).map_err(de::Error::custom)
+    }
+}
+
+pub trait FieldElementOps: Sized {
+    fn new(num: UBig, prime: UBig) -> Result<Self, EccError>;
+    fn from_reduced(num: UBig, prime: UBig) -> Self;
+    fn from_u64(num: u64, prime: UBig) -> Self;
+    fn from_i64(num: i64, prime: UBig) -> Self;
+    /// Reads `bytes` as a big-endian integer, which must be below `prime`.
+    fn from_be_bytes(bytes: &[u8], prime: UBig) -> Result<Self, EccError>;
+    /// Reads a big-endian hex string such as a curve constant, which must be
+    /// below `prime`.
+    fn from_hex(hex_num: &str, prime: UBig) -> Result<Self, ValueError>;
+    /// Big-endian encoding left-padded to `len` bytes. Panics if the value
+    /// does not fit.
+    fn to_be_bytes(&self, len: usize) -> Vec<u8>;
     fn pow(self, power: i128) -> Self;
+    /// Raises to a non-negative exponent of any size, such as `p - 2`. The
+    /// exponent is used as given, not reduced mod `p - 1`.
+    fn pow_ubig(self, exp: &UBig) -> Self;
+    fn checked_add(&self, rhs: &Self) -> Result<Self, EccError>;
+    fn checked_sub(&self, rhs: &Self) -> Result<Self, EccError>;
+    fn checked_mul(&self, rhs: &Self) -> Result<Self, EccError>;
+    fn checked_div(&self, rhs: &Self) -> Result<Self, EccError>;
+    /// Orders two elements of the same field by their numbers, as `cmp`
+    /// does, but with an error instead of a panic across primes.
+    fn cmp_num(&self, other: &Self) -> Result<Ordering, EccError>;
+    fn inverse(&self) -> Result<Self, EccError>;
+    fn sqrt(&self) -> Option<Self>;
+    /// `2 * self` with a shift and one reduction, without cloning an
+    /// operand as `self + self` does.
+    fn double(&self) -> Self;
+    /// `k * self` for a small integer constant, reduced once, without
+    /// building `k` as a field element first.
+    fn mul_small(&self, k: u64) -> Self;
+    /// Whether this is the additive identity, without building a zero
+    /// element to compare against.
+    fn is_zero(&self) -> bool;
+    /// Whether this is the multiplicative identity.
+    fn is_one(&self) -> bool;
+    /// Equality that scans every byte of both values without an early exit,
+    /// for comparisons on secret-derived data. Values are compared modulo
+    /// the prime; elements of different fields are unequal. Like
+    /// `scalar_mul_ct`, this removes the data-dependent branch rather than
+    /// promising constant-time `UBig` arithmetic.
+    fn ct_eq(&self, other: &Self) -> bool;
+}
+
+impl<'a> Add<&'a FieldElement> for &'a FieldElement {
+    type Output = FieldElement;
+    fn add(self, rhs: &FieldElement) -> FieldElement {
+        assert_eq!(self.prime, rhs.prime);
+        // a single reduction of the sum is canonical even for operands at or
+        // above prime, so those need no reducing first
+        FieldElement {
+            num: (&self.num + &rhs.num) % &self.prime,
+            prime: self.prime.clone(),
+        }
+    }
 }
 
 impl Add for FieldElement {
     type Output = FieldElement;
     fn add(self, rhs: Self) -> Self {
+        &self + &rhs
+    }
+}
+
+impl AddAssign<&FieldElement> for FieldElement {
+    fn add_assign(&mut self, rhs: &FieldElement) {
+        *self = &*self + rhs;
+    }
+}
+
+impl AddAssign for FieldElement {
+    fn add_assign(&mut self, rhs: Self) {
+        *self += &rhs;
+    }
+}
+
+impl<'a> Sub<&'a FieldElement> for &'a FieldElement {
+    type Output = FieldElement;
+    fn sub(self, rhs: &FieldElement) -> FieldElement {
         assert_eq!(self.prime, rhs.prime);
+        // reducing rhs first keeps prime - rhs from underflowing
+        let lhs = &self.num % &self.prime;
+        let rhs = &rhs.num % &self.prime;
         FieldElement {
-            num: (self.num + rhs.num) % self.prime.clone(),
-            prime: self.prime,
+            num: (lhs + &self.prime - rhs) % &self.prime,
+            prime: self.prime.clone(),
         }
     }
 }
@@ -28,17 +153,29 @@ impl Add for FieldElement {
 impl Sub for FieldElement {
     type Output = FieldElement;
     fn sub(self, rhs: Self) -> Self {
-        let new_cloned = self.prime.clone();
-        let ret_cloned = self.prime.clone();
-        assert_eq!(self.prime.clone(), rhs.prime);
-        let new_num = if self.num < rhs.num {
-            self.prime - ((rhs.num - self.num) % new_cloned)
-        } else {
-            self.num - rhs.num
-        };
+        &self - &rhs
+    }
+}
+
+impl SubAssign<&FieldElement> for FieldElement {
+    fn sub_assign(&mut self, rhs: &FieldElement) {
+        *self = &*self - rhs;
+    }
+}
+
+impl SubAssign for FieldElement {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self -= &rhs;
+    }
+}
+
+impl Neg for &FieldElement {
+    type Output = FieldElement;
+    fn neg(self) -> FieldElement {
         FieldElement {
-            num: new_num,
-            prime: ret_cloned,
+            // the outer reduction keeps -0 at 0 rather than prime
+            num: (&self.prime - (&self.num % &self.prime)) % &self.prime,
+            prime: self.prime.clone(),
         }
     }
 }
@@ -46,12 +183,7 @@ impl Sub for FieldElement {
 impl Neg for FieldElement {
     type Output = FieldElement;
     fn neg(self) -> Self {
-        let new_cloned = self.prime.clone();
-        let ret_cloned = self.prime.clone();
-        FieldElement {
-            num: self.prime - (self.num % new_cloned),
-            prime: ret_cloned,
-        }
+        -&self
     }
 }
 
@@ -61,14 +193,78 @@ impl PartialEq for FieldElement {
     }
 }
 
+impl Eq for FieldElement {}
+
+/// Orders by `num`, for range checks such as low-s. Like the arithmetic
+/// operators, this panics on elements of different fields; `cmp_num` is
+/// the fallible form.
+impl Ord for FieldElement {
+    fn cmp(&self, other: &FieldElement) -> Ordering {
+        assert_eq!(self.prime, other.prime);
+        self.num.cmp(&other.num)
+    }
+}
+
+impl PartialOrd for FieldElement {
+    fn partial_cmp(&self, other: &FieldElement) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for FieldElement {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // the same fields PartialEq compares
+        self.num.hash(state);
+        self.prime.hash(state);
+    }
+}
+
+impl fmt::Display for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "FieldElement_{}({})", self.prime, self.num)
+    }
+}
+
+impl<'a> Mul<&'a FieldElement> for &'a FieldElement {
+    type Output = FieldElement;
+    fn mul(self, rhs: &FieldElement) -> FieldElement {
+        assert_eq!(self.prime, rhs.prime);
+        // as with addition, reducing the product covers unreduced operands
+        FieldElement {
+            num: (&self.num * &rhs.num) % &self.prime,
+            prime: self.prime.clone(),
+        }
+    }
+}
+
 impl Mul for FieldElement {
     type Output = FieldElement;
     fn mul(self, rhs: Self) -> Self {
-        let ret_cloned = self.prime.clone();
+        &self * &rhs
+    }
+}
+
+impl MulAssign<&FieldElement> for FieldElement {
+    fn mul_assign(&mut self, rhs: &FieldElement) {
+        *self = &*self * rhs;
+    }
+}
+
+impl MulAssign for FieldElement {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self *= &rhs;
+    }
+}
+
+impl<'a> Div<&'a FieldElement> for &'a FieldElement {
+    type Output = FieldElement;
+    fn div(self, rhs: &FieldElement) -> FieldElement {
         assert_eq!(self.prime, rhs.prime);
+        // Fermat's little theorem: b^(p-1) == 1 mod p, so b^(p-2) is the inverse of b
+        let inverse = modpow(&rhs.num, &(&self.prime - ubig!(2)), &self.prime);
         FieldElement {
-            num: (self.num * rhs.num) % self.prime,
-            prime: ret_cloned,
+            num: (&self.num * inverse) % &self.prime,
+            prime: self.prime.clone(),
         }
     }
 }
@@ -76,41 +272,287 @@ impl Mul for FieldElement {
 impl Div for FieldElement {
     type Output = FieldElement;
     fn div(self, rhs: Self) -> Self {
-        let ret_cloned = self.prime.clone();
-        assert_eq!(self.prime, rhs.prime);
-        FieldElement {
-            num: (self.num / rhs.num) % self.prime,
-            prime: ret_cloned,
-        }
+        &self / &rhs
+    }
+}
+
+/// Adds up the elements of one field. An element does not exist without
+/// its prime, so an empty iterator has no zero to return and this panics;
+/// when the iterator may be empty, fold from an explicit zero instead:
+/// `iter.fold(FieldElement::from_u64(0, prime), |acc, x| acc + x)`.
+impl Sum for FieldElement {
+    fn sum<I: Iterator<Item = FieldElement>>(iter: I) -> FieldElement {
+        iter.reduce(|acc, x| acc + x)
+            .expect("cannot sum an empty iterator of field elements")
+    }
+}
+
+impl<'a> Sum<&'a FieldElement> for FieldElement {
+    fn sum<I: Iterator<Item = &'a FieldElement>>(iter: I) -> FieldElement {
+        iter.cloned().sum()
+    }
+}
+
+/// Multiplies the elements of one field. Like `Sum`, this panics on an
+/// empty iterator; fold from `FieldElement::from_u64(1, prime)` when the
+/// iterator may be empty.
+impl Product for FieldElement {
+    fn product<I: Iterator<Item = FieldElement>>(iter: I) -> FieldElement {
+        iter.reduce(|acc, x| acc * x)
+            .expect("cannot multiply an empty iterator of field elements")
+    }
+}
+
+impl<'a> Product<&'a FieldElement> for FieldElement {
+    fn product<I: Iterator<Item = &'a FieldElement>>(iter: I) -> FieldElement {
+        iter.cloned().product()
     }
 }
 
 impl FieldElementOps for FieldElement {
-    fn new(num: UBig, prime: UBig) -> Result<FieldElement, ValueError> {
+    fn new(num: UBig, prime: UBig) -> Result<FieldElement, EccError> {
         match num >= prime {
-            true => Err(ValueError {
-                message: format!("num {} not in field range 0 to {}", num, prime - ubig!(1)),
-            }),
+            true => Err(EccError::NotInFieldRange { num, prime }),
             false => Ok(FieldElement { num, prime }),
         }
     }
+    fn from_reduced(num: UBig, prime: UBig) -> FieldElement {
+        FieldElement {
+            num: num % &prime,
+            prime,
+        }
+    }
+    fn from_u64(num: u64, prime: UBig) -> FieldElement {
+        FieldElement::from_reduced(UBig::from(num), prime)
+    }
+    fn from_i64(num: i64, prime: UBig) -> FieldElement {
+        // rem_euclid wraps negatives up into [0, prime)
+        let num = IBig::from(num).rem_euclid(IBig::from(&prime));
+        FieldElement {
+            num: UBig::try_from(num).unwrap(),
+            prime,
+        }
+    }
+    fn from_be_bytes(bytes: &[u8], prime: UBig) -> Result<FieldElement, EccError> {
+        FieldElement::new(UBig::from_be_bytes(bytes), prime)
+    }
+    fn from_hex(hex_num: &str, prime: UBig) -> Result<FieldElement, ValueError> {
+        Ok(FieldElement::from_be_bytes(&hex::decode(hex_num)?, prime)?)
+    }
+    fn to_be_bytes(&self, len: usize) -> Vec<u8> {
+        let bytes = self.num.to_be_bytes();
+        assert!(
+            bytes.len() <= len,
+            "field element does not fit in {} bytes",
+            len
+        );
+        let mut out = vec![0u8; len - bytes.len()];
+        out.extend_from_slice(&bytes);
+        out
+    }
     fn pow(self, power: i128) -> Self {
-        let exp = if power < 0 {
-            1_usize + (-1 * power) as usize
-        } else {
-            power as usize
-        };
+        // a^(p-1) == 1 for non-zero a, so the exponent only matters mod p-1;
+        // rem_euclid also wraps negative exponents into range
+        let order = IBig::from(self.prime.clone() - ubig!(1));
+        let exp = UBig::try_from(IBig::from(power).rem_euclid(order)).unwrap();
         FieldElement {
-            // num: self.num.pow(power) % self.prime,
-            num: self.num.pow(exp) % self.prime.clone(),
+            num: modpow(&self.num, &exp, &self.prime),
             prime: self.prime,
         }
     }
+    fn pow_ubig(self, exp: &UBig) -> Self {
+        FieldElement {
+            num: modpow(&self.num, exp, &self.prime),
+            prime: self.prime,
+        }
+    }
+    fn checked_add(&self, rhs: &Self) -> Result<Self, EccError> {
+        check_same_field(self, rhs)?;
+        Ok(self + rhs)
+    }
+    fn checked_sub(&self, rhs: &Self) -> Result<Self, EccError> {
+        check_same_field(self, rhs)?;
+        Ok(self - rhs)
+    }
+    fn checked_mul(&self, rhs: &Self) -> Result<Self, EccError> {
+        check_same_field(self, rhs)?;
+        Ok(self * rhs)
+    }
+    fn checked_div(&self, rhs: &Self) -> Result<Self, EccError> {
+        check_same_field(self, rhs)?;
+        Ok(self / rhs)
+    }
+    fn cmp_num(&self, other: &Self) -> Result<Ordering, EccError> {
+        check_same_field(self, other)?;
+        Ok(self.num.cmp(&other.num))
+    }
+    fn inverse(&self) -> Result<Self, EccError> {
+        // extended Euclidean algorithm; unlike pow(p-2) this also works when
+        // the modulus is composite
+        let modulus = IBig::from(&self.prime);
+        let (mut old_r, mut r) = (IBig::from(&self.num), modulus.clone());
+        let (mut old_s, mut s) = (ibig!(1), ibig!(0));
+        while r != ibig!(0) {
+            let q = &old_r / &r;
+            let next_r = &old_r - &q * &r;
+            old_r = mem::replace(&mut r, next_r);
+            let next_s = &old_s - &q * &s;
+            old_s = mem::replace(&mut s, next_s);
+        }
+        match old_r == ibig!(1) {
+            true => Ok(FieldElement {
+                num: UBig::try_from(old_s.rem_euclid(modulus)).unwrap(),
+                prime: self.prime.clone(),
+            }),
+            false => Err(EccError::NoInverse),
+        }
+    }
+    fn sqrt(&self) -> Option<Self> {
+        let p = &self.prime;
+        let one = ubig!(1);
+        let n = &self.num % p;
+        if n == ubig!(0) || p == &ubig!(2) {
+            return Some(FieldElement::from_reduced(n, p.clone()));
+        }
+        // Euler's criterion: n is a quadratic residue iff n^((p-1)/2) == 1
+        if modpow(&n, &((p - &one) >> 1), p) != one {
+            return None;
+        }
+        if p % ubig!(4) == ubig!(3) {
+            let root = modpow(&n, &((p + &one) >> 2), p);
+            return Some(FieldElement::from_reduced(root, p.clone()));
+        }
+        // Tonelli-Shanks: write p - 1 = q * 2^s with q odd
+        let s = (p - &one).trailing_zeros().unwrap();
+        let q = (p - &one) >> s;
+        let mut z = ubig!(2);
+        while modpow(&z, &((p - &one) >> 1), p) == one {
+            z += ubig!(1);
+        }
+        let mut m = s;
+        let mut c = modpow(&z, &q, p);
+        let mut t = modpow(&n, &q, p);
+        let mut root = modpow(&n, &((&q + &one) >> 1), p);
+        while t != one {
+            // find the least i with t^(2^i) == 1
+            let mut i = 0;
+            let mut t_sq = t.clone();
+            while t_sq != one {
+                t_sq = &t_sq * &t_sq % p;
+                i += 1;
+            }
+            let b = modpow(&c, &(ubig!(1) << (m - i - 1)), p);
+            m = i;
+            c = &b * &b % p;
+            t = &t * &c % p;
+            root = &root * &b % p;
+        }
+        Some(FieldElement::from_reduced(root, p.clone()))
+    }
+    fn double(&self) -> Self {
+        FieldElement {
+            num: (&self.num << 1) % &self.prime,
+            prime: self.prime.clone(),
+        }
+    }
+    fn mul_small(&self, k: u64) -> Self {
+        FieldElement {
+            num: (&self.num * UBig::from(k)) % &self.prime,
+            prime: self.prime.clone(),
+        }
+    }
+    fn is_zero(&self) -> bool {
+        self.num == ubig!(0)
+    }
+    fn is_one(&self) -> bool {
+        self.num == ubig!(1)
+    }
+    fn ct_eq(&self, other: &Self) -> bool {
+        if self.prime != other.prime {
+            return false;
+        }
+        // both sides padded to the prime's width, so the loop length is public
+        let len = self.prime.bit_len().div_ceil(8);
+        let lhs = FieldElement::from_reduced(self.num.clone(), self.prime.clone()).to_be_bytes(len);
+        let rhs =
+            FieldElement::from_reduced(other.num.clone(), other.prime.clone()).to_be_bytes(len);
+        lhs.iter()
+            .zip(rhs.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+    }
+}
+
+fn check_same_field(left: &FieldElement, right: &FieldElement) -> Result<(), EccError> {
+    match left.prime == right.prime {
+        true => Ok(()),
+        false => Err(EccError::PrimeMismatch {
+            left: left.prime.clone(),
+            right: right.prime.clone(),
+        }),
+    }
+}
+
+/// Inverts every element with a single field inversion (Montgomery's trick):
+/// the running products are inverted once and then unwound with two
+/// multiplications per element. All elements must share a prime and none may
+/// be zero.
+pub fn batch_inverse(elements: &[FieldElement]) -> Result<Vec<FieldElement>, ValueError> {
+    let first = match elements.first() {
+        Some(first) => first,
+        None => return Ok(Vec::new()),
+    };
+    let mut prefix = Vec::with_capacity(elements.len());
+    let mut acc = FieldElement::from_reduced(ubig!(1), first.prime.clone());
+    for element in elements {
+        check_same_field(first, element)?;
+        if element.is_zero() {
+            return Err(EccError::NoInverse.into());
+        }
+        acc = &acc * element;
+        prefix.push(acc.clone());
+    }
+    // inv holds (e_0 * ... * e_i)^-1 at the top of each iteration
+    let mut inv = acc.inverse()?;
+    let mut out = vec![inv.clone(); elements.len()];
+    for i in (0..elements.len()).rev() {
+        out[i] = match i {
+            0 => inv.clone(),
+            _ => &inv * &prefix[i - 1],
+        };
+        inv = &inv * &elements[i];
+    }
+    Ok(out)
+}
+
+/// Computes `base^exp mod modulus` by square-and-multiply, reducing after
+/// every step so no intermediate grows past `modulus^2`. `exp` may be larger
+/// than `modulus`.
+pub fn modpow(base: &UBig, exp: &UBig, modulus: &UBig) -> UBig {
+    let base = base % modulus;
+    let mut result = ubig!(1) % modulus;
+    for i in (0..exp.bit_len()).rev() {
+        result = &result * &result % modulus;
+        if exp.bit(i) {
+            result = &result * &base % modulus;
+        }
+    }
+    result
+}
+
+/// Big-endian encoding of `num`, left-padded to 32 bytes.
+pub(crate) fn to_bytes32(num: &UBig) -> [u8; 32] {
+    let bytes = num.to_be_bytes();
+    let mut out = [0u8; 32];
+    out[32 - bytes.len()..].copy_from_slice(&bytes);
+    out
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
+
     #[test]
     fn test_new() {
         let a = FieldElement::new(ubig!(3), ubig!(13));
@@ -123,6 +565,22 @@ mod test {
         assert!(d.is_ok());
     }
 
+    #[test]
+    fn test_from_reduced() {
+        assert_eq!(
+            FieldElement::from_reduced(ubig!(14), ubig!(13)).num,
+            ubig!(1)
+        );
+        assert_eq!(
+            FieldElement::from_reduced(ubig!(13), ubig!(13)),
+            FieldElement::new(ubig!(0), ubig!(13)).unwrap()
+        );
+        assert_eq!(
+            FieldElement::from_reduced(ubig!(5), ubig!(13)),
+            FieldElement::new(ubig!(5), ubig!(13)).unwrap()
+        );
+    }
+
     #[test]
     fn test_ne() {
         let a = FieldElement::new(ubig!(2), ubig!(13)).unwrap();
@@ -133,6 +591,33 @@ mod test {
         assert!(b != c);
     }
 
+    #[test]
+    fn test_display() {
+        let a = FieldElement::new(ubig!(3), ubig!(13)).unwrap();
+        assert_eq!(format!("{}", a), "FieldElement_13(3)");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let a = FieldElement::new(ubig!(200), ubig!(223)).unwrap();
+        let json = serde_json::to_string(&a).unwrap();
+        assert_eq!(json, r#"{"num":"c8","prime":"df"}"#);
+        let b: FieldElement = serde_json::from_str(&json).unwrap();
+        assert_eq!(a, b);
+        assert!(serde_json::from_str::<FieldElement>(r#"{"num":"zz","prime":"df"}"#).is_err());
+        // num must be below prime, as FieldElement::new requires
+        let err = serde_json::from_str::<FieldElement>(r#"{"num":"ff","prime":"df"}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("num 255 not in field range 0 to 222"),
+            "{}",
+            err
+        );
+        assert!(serde_json::from_str::<FieldElement>(r#"{"num":"df","prime":"df"}"#).is_err());
+    }
+
     #[test]
     fn test_add() {
         let mut a = FieldElement::new(ubig!(2), ubig!(13)).unwrap();
@@ -180,13 +665,557 @@ mod test {
     fn test_div() {
         let mut a = FieldElement::new(ubig!(3), ubig!(31)).unwrap();
         let mut b = FieldElement::new(ubig!(24), ubig!(31)).unwrap();
-        assert_eq!(a / b, FieldElement::new(ubig!(4), ubig!(31)));
-        a = FieldElement::new(ubig!(17), ubig!(31)).unwrap();
-        assert_eq!(a.pow(-3), FieldElement::new(ubig!(29), ubig!(31)));
+        assert_eq!(a / b, FieldElement::new(ubig!(4), ubig!(31)).unwrap());
+        a = FieldElement::new(ubig!(2), ubig!(19)).unwrap();
+        b = FieldElement::new(ubig!(7), ubig!(19)).unwrap();
+        assert_eq!(a / b, FieldElement::new(ubig!(3), ubig!(19)).unwrap());
+        a = FieldElement::new(ubig!(7), ubig!(19)).unwrap();
+        b = FieldElement::new(ubig!(5), ubig!(19)).unwrap();
+        assert_eq!(a / b, FieldElement::new(ubig!(9), ubig!(19)).unwrap());
+        a = FieldElement::new(ubig!(1), ubig!(13)).unwrap();
+        b = FieldElement::new(ubig!(12), ubig!(13)).unwrap();
+        assert_eq!(a / b, FieldElement::new(ubig!(12), ubig!(13)).unwrap());
+    }
+
+    #[test]
+    fn test_pow_negative() {
+        let mut a = FieldElement::new(ubig!(17), ubig!(31)).unwrap();
+        assert_eq!(a.pow(-3), FieldElement::new(ubig!(29), ubig!(31)).unwrap());
         a = FieldElement::new(ubig!(4), ubig!(31)).unwrap();
-        b = FieldElement::new(ubig!(11), ubig!(31)).unwrap();
-        assert_eq!(a.pow(-3) * b, FieldElement::new(ubig!(13), ubig!(31)));
-        // println!("{:?}", a.pow(-3) * b);
-        // println!("{:?}", FieldElement::new(ubig!(12), ubig!(31)));
+        let b = FieldElement::new(ubig!(11), ubig!(31)).unwrap();
+        assert_eq!(
+            a.pow(-4) * b,
+            FieldElement::new(ubig!(13), ubig!(31)).unwrap()
+        );
+        a = FieldElement::new(ubig!(17), ubig!(31)).unwrap();
+        assert_eq!(a.clone().pow(-3), a.pow(31 - 1 - 3));
+        // -3 - 30 * 1_000_000 wraps to the same residue as -3
+        a = FieldElement::new(ubig!(17), ubig!(31)).unwrap();
+        assert_eq!(
+            a.pow(-30_000_003),
+            FieldElement::new(ubig!(29), ubig!(31)).unwrap()
+        );
+        a = FieldElement::new(ubig!(5), ubig!(13)).unwrap();
+        assert_eq!(a.pow(-1), FieldElement::new(ubig!(8), ubig!(13)).unwrap());
+    }
+
+    #[test]
+    fn test_pow_large() {
+        let mut a = FieldElement::new(ubig!(7), ubig!(13)).unwrap();
+        assert_eq!(a.pow(16), FieldElement::new(ubig!(9), ubig!(13)).unwrap());
+        a = FieldElement::new(ubig!(7), ubig!(13)).unwrap();
+        assert_eq!(a.pow(12), FieldElement::new(ubig!(1), ubig!(13)).unwrap());
+        a = FieldElement::new(ubig!(3), ubig!(31)).unwrap();
+        assert_eq!(
+            a.pow(i128::MAX),
+            FieldElement::new(ubig!(3), ubig!(31))
+                .unwrap()
+                .pow(i128::MAX % 30)
+        );
+    }
+
+    #[test]
+    fn test_pow_256_bit_prime() {
+        // secp256k1 field prime; a.pow(-1) needs an exponent of p - 2
+        let prime = UBig::from_str_radix(
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+            16,
+        )
+        .unwrap();
+        let a = FieldElement::new(ubig!(0xdeadbeef), prime.clone()).unwrap();
+        let one = FieldElement::new(ubig!(1), prime).unwrap();
+        assert_eq!(a.clone().pow(-1) * a.clone(), one);
+        assert_eq!(a.clone().pow(i128::MAX) * a.pow(-i128::MAX), one);
+    }
+
+    #[test]
+    fn test_modpow() {
+        assert_eq!(modpow(&ubig!(7), &ubig!(13), &ubig!(13)), ubig!(7));
+        assert_eq!(modpow(&ubig!(2), &ubig!(100), &ubig!(13)), ubig!(3));
+        assert_eq!(modpow(&ubig!(20), &ubig!(0), &ubig!(13)), ubig!(1));
+        assert_eq!(modpow(&ubig!(5), &ubig!(3), &ubig!(1)), ubig!(0));
+        let prime = UBig::from_str_radix(
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+            16,
+        )
+        .unwrap();
+        // 2^256 == 2^32 + 977 mod p
+        assert_eq!(modpow(&ubig!(2), &ubig!(256), &prime), ubig!(0x1000003d1));
+        assert_eq!(
+            modpow(&ubig!(3), &(prime.clone() - ubig!(1)), &prime),
+            ubig!(1)
+        );
+    }
+
+    #[test]
+    fn test_ref_ops() {
+        let a = FieldElement::new(ubig!(5), ubig!(13)).unwrap();
+        let b = FieldElement::new(ubig!(12), ubig!(13)).unwrap();
+        assert_eq!(&a + &b, FieldElement::new(ubig!(4), ubig!(13)).unwrap());
+        assert_eq!(&a - &b, FieldElement::new(ubig!(6), ubig!(13)).unwrap());
+        assert_eq!(&a * &b, FieldElement::new(ubig!(8), ubig!(13)).unwrap());
+        assert_eq!(&a / &b, FieldElement::new(ubig!(8), ubig!(13)).unwrap());
+        assert_eq!(-&a, FieldElement::new(ubig!(8), ubig!(13)).unwrap());
+        let zero = FieldElement::new(ubig!(0), ubig!(13)).unwrap();
+        assert_eq!(-&zero, zero);
+        // neither operand was moved
+        assert_eq!(&(&a * &b) / &b, a);
+        assert_eq!(a.clone() + b.clone(), &a + &b);
+    }
+
+    #[test]
+    fn test_assign_ops() {
+        let elements: Vec<FieldElement> = [3u32, 7, 11, 12]
+            .iter()
+            .map(|n| FieldElement::new(UBig::from(*n), ubig!(13)).unwrap())
+            .collect();
+        let mut acc = FieldElement::new(ubig!(0), ubig!(13)).unwrap();
+        for e in elements.iter() {
+            acc += e;
+        }
+        // 33 mod 13
+        assert_eq!(acc, FieldElement::new(ubig!(7), ubig!(13)).unwrap());
+        acc -= elements[3].clone();
+        assert_eq!(acc, FieldElement::new(ubig!(8), ubig!(13)).unwrap());
+        let mut prod = FieldElement::new(ubig!(1), ubig!(13)).unwrap();
+        for e in elements.iter() {
+            prod *= e;
+        }
+        // 3 * 7 * 11 * 12 == 2772 == 3 mod 13
+        assert_eq!(prod, FieldElement::new(ubig!(3), ubig!(13)).unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assign_ops_mismatched_prime() {
+        let mut a = FieldElement::new(ubig!(3), ubig!(13)).unwrap();
+        a += FieldElement::new(ubig!(3), ubig!(31)).unwrap();
+    }
+
+    #[test]
+    fn test_checked_ops() {
+        let a = FieldElement::new(ubig!(5), ubig!(13)).unwrap();
+        let b = FieldElement::new(ubig!(12), ubig!(13)).unwrap();
+        let c = FieldElement::new(ubig!(5), ubig!(31)).unwrap();
+        assert_eq!(a.checked_add(&b).unwrap(), &a + &b);
+        assert_eq!(a.checked_sub(&b).unwrap(), &a - &b);
+        assert_eq!(a.checked_mul(&b).unwrap(), &a * &b);
+        assert_eq!(a.checked_div(&b).unwrap(), &a / &b);
+        assert!(a.checked_add(&c).is_err());
+        assert!(a.checked_sub(&c).is_err());
+        assert!(a.checked_mul(&c).is_err());
+        assert_eq!(
+            a.checked_div(&c).unwrap_err(),
+            EccError::PrimeMismatch {
+                left: ubig!(13),
+                right: ubig!(31)
+            }
+        );
+    }
+
+    #[test]
+    fn test_ord() {
+        let three = FieldElement::new(ubig!(3), ubig!(13)).unwrap();
+        let ten = FieldElement::new(ubig!(10), ubig!(13)).unwrap();
+        assert!(three < ten);
+        assert!(ten >= three);
+        assert_eq!(three.cmp_num(&ten), Ok(Ordering::Less));
+        assert_eq!(ten.cmp_num(&ten.clone()), Ok(Ordering::Equal));
+        assert_eq!(core::cmp::max(three, ten.clone()), ten);
+        let other = FieldElement::new(ubig!(3), ubig!(31)).unwrap();
+        assert_eq!(
+            ten.cmp_num(&other),
+            Err(EccError::PrimeMismatch {
+                left: ubig!(13),
+                right: ubig!(31)
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ord_mismatched_prime() {
+        let a = FieldElement::new(ubig!(3), ubig!(13)).unwrap();
+        let _ = a < FieldElement::new(ubig!(10), ubig!(31)).unwrap();
+    }
+
+    #[test]
+    fn test_inverse() {
+        let a = FieldElement::new(ubig!(5), ubig!(13)).unwrap();
+        assert_eq!(a.inverse().unwrap(), a.clone().pow(-1));
+        let prime = UBig::from_str_radix(
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+            16,
+        )
+        .unwrap();
+        let b = FieldElement::new(ubig!(0xdeadbeef), prime.clone()).unwrap();
+        let one = FieldElement::new(ubig!(1), prime).unwrap();
+        assert_eq!(&b.inverse().unwrap() * &b, one);
+        assert_eq!(b.inverse().unwrap(), b.pow(-1));
+        // composite modulus: 7 * 13 == 91 == 1 mod 15, but 6 shares a factor with 15
+        let c = FieldElement::new(ubig!(7), ubig!(15)).unwrap();
+        assert_eq!(
+            c.inverse().unwrap(),
+            FieldElement::new(ubig!(13), ubig!(15)).unwrap()
+        );
+        assert_eq!(
+            FieldElement::new(ubig!(6), ubig!(15)).unwrap().inverse(),
+            Err(EccError::NoInverse)
+        );
+        assert!(FieldElement::new(ubig!(0), ubig!(13))
+            .unwrap()
+            .inverse()
+            .is_err());
+    }
+
+    #[test]
+    fn test_sqrt() {
+        // 223 % 4 == 3 takes the fast path; 13 and 17 need Tonelli-Shanks
+        for prime in [13u32, 17, 223].iter() {
+            let mut residues = 0;
+            for num in 0..*prime {
+                let a = FieldElement::new(UBig::from(num), UBig::from(*prime)).unwrap();
+                let squares = (0..*prime).any(|r| r * r % prime == num);
+                match a.sqrt() {
+                    Some(root) => {
+                        assert!(squares);
+                        assert_eq!(&root * &root, a);
+                        residues += 1;
+                    }
+                    None => assert!(!squares),
+                }
+            }
+            // zero plus (p - 1) / 2 non-zero residues
+            assert_eq!(residues, 1 + (prime - 1) / 2);
+        }
+        assert!(FieldElement::new(ubig!(2), ubig!(13))
+            .unwrap()
+            .sqrt()
+            .is_none());
+    }
+
+    #[test]
+    fn test_sqrt_secp256k1() {
+        let prime = UBig::from_str_radix(
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+            16,
+        )
+        .unwrap();
+        let gy = FieldElement::new(
+            UBig::from_str_radix(
+                "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+                16,
+            )
+            .unwrap(),
+            prime,
+        )
+        .unwrap();
+        let root = (&gy * &gy).sqrt().unwrap();
+        assert!(root == gy || root == -&gy);
+    }
+
+    #[test]
+    fn test_batch_inverse() {
+        let prime = ubig!(223);
+        let elements: Vec<FieldElement> = (1..=100u32)
+            .map(|n| FieldElement::new(UBig::from(n * 2), prime.clone()).unwrap())
+            .collect();
+        let inverses = batch_inverse(&elements).unwrap();
+        assert_eq!(inverses.len(), 100);
+        for (element, inverse) in elements.iter().zip(inverses.iter()) {
+            assert_eq!(*inverse, element.inverse().unwrap());
+        }
+        assert_eq!(batch_inverse(&[]).unwrap(), vec![]);
+
+        let mut with_zero = elements.clone();
+        with_zero.push(FieldElement::new(ubig!(0), prime.clone()).unwrap());
+        assert!(batch_inverse(&with_zero).is_err());
+
+        let mut mixed = elements;
+        mixed.push(FieldElement::new(ubig!(3), ubig!(31)).unwrap());
+        assert!(batch_inverse(&mixed).is_err());
+    }
+
+    #[test]
+    fn test_from_i64() {
+        assert_eq!(FieldElement::from_i64(-1, ubig!(13)).num, ubig!(12));
+        assert_eq!(FieldElement::from_i64(-27, ubig!(13)).num, ubig!(12));
+        assert_eq!(FieldElement::from_i64(5, ubig!(13)).num, ubig!(5));
+        assert_eq!(
+            FieldElement::from_i64(i64::MIN, ubig!(13)),
+            FieldElement::from_u64(1u64 << 63, ubig!(13)).neg()
+        );
+    }
+
+    #[test]
+    fn test_from_u64() {
+        assert_eq!(
+            FieldElement::from_u64(4, ubig!(31)),
+            FieldElement::new(ubig!(4), ubig!(31)).unwrap()
+        );
+        assert_eq!(FieldElement::from_u64(35, ubig!(31)).num, ubig!(4));
+        assert_eq!(FieldElement::from_u64(u64::MAX, ubig!(31)).num, ubig!(15));
+    }
+
+    #[test]
+    fn test_be_bytes() {
+        let prime = crate::secp256k1::p();
+        let bytes = [0x00, 0x01, 0x02]
+            .iter()
+            .cycle()
+            .take(32)
+            .cloned()
+            .collect::<Vec<u8>>();
+        let a = FieldElement::from_be_bytes(&bytes, prime.clone()).unwrap();
+        assert_eq!(a.to_be_bytes(32), bytes);
+        // leading zero bytes are padded back in
+        assert_eq!(a.to_be_bytes(33)[..2], [0x00, 0x00]);
+        let zero = FieldElement::from_be_bytes(&[], prime.clone()).unwrap();
+        assert_eq!(zero.to_be_bytes(32), vec![0u8; 32]);
+        // p itself is out of range
+        let p_bytes = prime.to_be_bytes();
+        assert_eq!(
+            FieldElement::from_be_bytes(&p_bytes, prime.clone()).unwrap_err(),
+            EccError::NotInFieldRange {
+                num: prime.clone(),
+                prime,
+            }
+        );
+    }
+
+    #[test]
+    fn test_pow_ubig() {
+        let prime = crate::secp256k1::p();
+        let a = FieldElement::from_hex(
+            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            prime.clone(),
+        )
+        .unwrap();
+        let inverse = a.clone().pow_ubig(&(&prime - ubig!(2)));
+        assert_eq!(inverse, a.inverse().unwrap());
+        assert_eq!(&inverse * &a, FieldElement::from_u64(1, prime.clone()));
+        // small exponents agree with pow
+        assert_eq!(a.clone().pow_ubig(&ubig!(3)), a.clone().pow(3));
+        // the exponent is not reduced: 0^(p-1) stays 0, 0^0 is 1
+        let zero = FieldElement::from_u64(0, prime.clone());
+        assert_eq!(zero.clone().pow_ubig(&(&prime - ubig!(1))), zero);
+        assert_eq!(zero.pow_ubig(&ubig!(0)).num, ubig!(1));
+    }
+
+    #[test]
+    fn test_sum_product() {
+        let prime = ubig!(13);
+        let elements = [1, 2, 3]
+            .iter()
+            .map(|n| FieldElement::from_u64(*n, prime.clone()))
+            .collect::<Vec<_>>();
+        let six = FieldElement::from_u64(6, prime.clone());
+        assert_eq!(elements.iter().sum::<FieldElement>(), six);
+        assert_eq!(elements.iter().product::<FieldElement>(), six);
+        // 4 + 5 + 6 = 15 and 4 * 5 * 6 = 120 wrap around 13
+        let owned = (4..7).map(|n| FieldElement::from_u64(n, prime.clone()));
+        assert_eq!(owned.clone().sum::<FieldElement>().num, ubig!(2));
+        assert_eq!(owned.product::<FieldElement>().num, ubig!(3));
+        // an explicit seed handles the empty case
+        let zero = FieldElement::from_u64(0, prime.clone());
+        assert_eq!(
+            elements[..0].iter().fold(zero.clone(), |acc, x| &acc + x),
+            zero
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot sum an empty iterator")]
+    fn test_sum_empty() {
+        Vec::<FieldElement>::new().into_iter().sum::<FieldElement>();
+    }
+
+    #[test]
+    fn test_from_hex() {
+        use crate::point::PointOps;
+        let prime = crate::secp256k1::p();
+        let gx = FieldElement::from_hex(
+            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            prime.clone(),
+        )
+        .unwrap();
+        let gy = FieldElement::from_hex(
+            "483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8",
+            prime.clone(),
+        )
+        .unwrap();
+        let g = crate::secp256k1::g();
+        assert_eq!(g.x, Some(gx.clone()));
+        assert_eq!(g.y, Some(gy.clone()));
+        assert_eq!(
+            crate::point::Point::new(Some(gx), Some(gy), g.a.clone(), g.b.clone()).unwrap(),
+            g
+        );
+        // p itself is out of range
+        let err = FieldElement::from_hex(
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+            prime.clone(),
+        )
+        .unwrap_err();
+        assert!(err.message.starts_with("num "));
+        assert!(FieldElement::from_hex("7g", prime.clone()).is_err());
+        assert!(FieldElement::from_hex("abc", prime).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn test_to_be_bytes_too_short() {
+        FieldElement::new(ubig!(256), ubig!(65537))
+            .unwrap()
+            .to_be_bytes(1);
+    }
+
+    #[test]
+    fn test_sub_boundary() {
+        let prime = ubig!(13);
+        let fe = |num: u32| FieldElement::new(UBig::from(num), prime.clone()).unwrap();
+        assert_eq!(fe(12) - fe(12), fe(0));
+        assert_eq!(fe(0) - fe(12), fe(1));
+        assert_eq!(fe(12) - fe(0), fe(12));
+        assert_eq!(fe(0) - fe(1), fe(12));
+        // operands at or above the prime, as a raw struct literal allows
+        let raw = |num: u32| FieldElement {
+            num: UBig::from(num),
+            prime: prime.clone(),
+        };
+        assert_eq!(raw(13) - fe(1), fe(12));
+        assert_eq!(fe(1) - raw(13), fe(1));
+        assert_eq!(raw(27) - raw(14), fe(0));
+        assert_eq!(fe(3) - raw(30), fe(12));
+    }
+
+    #[test]
+    fn test_add_mul_unreduced() {
+        let prime = ubig!(13);
+        let fe = |num: u32| FieldElement::new(UBig::from(num), prime.clone()).unwrap();
+        let raw = |num: u32| FieldElement {
+            num: UBig::from(num),
+            prime: prime.clone(),
+        };
+        assert_eq!(raw(20) + raw(30), fe(11));
+        assert_eq!(raw(13) + fe(0), fe(0));
+        assert_eq!(raw(20) * raw(30), fe(2));
+        assert_eq!(raw(26) * fe(5), fe(0));
+        assert_eq!(raw(20) / raw(30), fe(7) / fe(4));
+    }
+
+    #[test]
+    fn test_double_mul_small() {
+        for num in [0u64, 1, 6, 7, 12] {
+            let a = FieldElement::from_u64(num, ubig!(13));
+            assert_eq!(a.double(), &a + &a);
+            assert_eq!(a.mul_small(3), &(&a + &a) + &a);
+            assert_eq!(a.mul_small(0), FieldElement::from_u64(0, ubig!(13)));
+            assert_eq!(a.mul_small(1), a);
+            assert_eq!(
+                a.mul_small(u64::MAX),
+                &a * &FieldElement::from_u64(u64::MAX, ubig!(13))
+            );
+        }
+        let p = crate::secp256k1::p();
+        let big = FieldElement::from_reduced(&p - ubig!(1), p.clone());
+        assert_eq!(
+            big.double(),
+            FieldElement::from_reduced(&p - ubig!(2), p.clone())
+        );
+        assert_eq!(big.double(), &big + &big);
+        assert_eq!(big.mul_small(8), &big * &FieldElement::from_u64(8, p));
+    }
+
+    #[test]
+    fn test_is_zero_is_one() {
+        let zero = FieldElement::new(ubig!(0), ubig!(13)).unwrap();
+        let one = FieldElement::new(ubig!(1), ubig!(13)).unwrap();
+        assert!(zero.is_zero());
+        assert!(!zero.is_one());
+        assert!(one.is_one());
+        assert!(!one.is_zero());
+        let twelve = FieldElement::from_u64(12, ubig!(13));
+        assert!(!twelve.is_zero() && !twelve.is_one());
+        assert!((&twelve + &one).is_zero());
+        assert!((&twelve * &twelve).is_one());
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        let a = FieldElement::new(ubig!(7), ubig!(13)).unwrap();
+        assert!(a.ct_eq(&a.clone()));
+        assert!(!a.ct_eq(&FieldElement::new(ubig!(8), ubig!(13)).unwrap()));
+        assert!(!a.ct_eq(&FieldElement::new(ubig!(7), ubig!(17)).unwrap()));
+        let unreduced = FieldElement {
+            num: ubig!(20),
+            prime: ubig!(13),
+        };
+        assert!(a.ct_eq(&unreduced));
+        // values differing only in a high byte of a 256-bit field
+        let p = crate::secp256k1::p();
+        let x = FieldElement::new(ubig!(1) << 200, p.clone()).unwrap();
+        assert!(x.ct_eq(&FieldElement::new(ubig!(1) << 200, p.clone()).unwrap()));
+        assert!(!x.ct_eq(&FieldElement::new(ubig!(1) << 201, p).unwrap()));
+    }
+
+    fn next_prime(start: u64) -> u64 {
+        (start.max(2)..)
+            .find(|n| (2..).take_while(|d| d * d <= *n).all(|d| n % d != 0))
+            .unwrap()
+    }
+
+    /// A prime below 10,000 and three elements of its field. The prime is
+    /// the next one after a drawn integer, so a failing case shrinks towards
+    /// `F_2` and small elements.
+    fn small_field() -> impl Strategy<Value = (FieldElement, FieldElement, FieldElement)> {
+        (2u64..10_000)
+            .prop_map(next_prime)
+            .prop_flat_map(|p| (Just(p), 0..p, 0..p, 0..p))
+            .prop_map(|(p, a, b, c)| {
+                let fe = |n| FieldElement::from_u64(n, UBig::from(p));
+                (fe(a), fe(b), fe(c))
+            })
+    }
+
+    /// Three elements of the secp256k1 base field.
+    fn secp_field() -> impl Strategy<Value = (FieldElement, FieldElement, FieldElement)> {
+        any::<[[u8; 32]; 3]>().prop_map(|bytes| {
+            let p = crate::secp256k1::p();
+            let fe = |b: &[u8; 32]| FieldElement::from_reduced(UBig::from_be_bytes(b), p.clone());
+            (fe(&bytes[0]), fe(&bytes[1]), fe(&bytes[2]))
+        })
+    }
+
+    fn check_axioms(a: &FieldElement, b: &FieldElement, c: &FieldElement) {
+        let p = a.prime.clone();
+        let zero = FieldElement::from_u64(0, p.clone());
+        let one = FieldElement::from_u64(1, p.clone());
+        assert_eq!(a + b, b + a);
+        assert_eq!(a * b, b * a);
+        assert_eq!(&(a + b) + c, a + &(b + c));
+        assert_eq!(&(a * b) * c, a * &(b * c));
+        assert_eq!(a * &(b + c), &(a * b) + &(a * c));
+        assert_eq!(a + &-a, zero);
+        assert_eq!(&(a - b) + b, *a);
+        if *a != zero {
+            let inv = a.inverse().unwrap();
+            assert_eq!(a * &inv, one);
+            assert_eq!(&one / a, inv);
+            assert_eq!(&(b / a) * a, *b);
+            assert_eq!(a.clone().pow_ubig(&(&p - ubig!(1))), one);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_small_field_axioms((a, b, c) in small_field()) {
+            check_axioms(&a, &b, &c);
+        }
+
+        #[test]
+        fn prop_secp256k1_field_axioms((a, b, c) in secp_field()) {
+            check_axioms(&a, &b, &c);
+        }
     }
 }
@@ -0,0 +1,925 @@
+use crate::base58;
+use crate::crypto::hmac_sha256;
+use crate::ecc::{to_bytes32, FieldElement, FieldElementOps};
+use crate::errors::{EccError, ValueError};
+use crate::generator::mul_generator;
+use crate::point::{Point, PointOps};
+use crate::scalar::Scalar;
+use crate::secp256k1;
+use ibig::{ubig, UBig};
+#[cfg(feature = "rand")]
+use rand_core::{CryptoRng, RngCore};
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// An ECDSA signature over secp256k1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature {
+    pub r: Scalar,
+    pub s: Scalar,
+}
+
+impl Signature {
+    /// Whether `s` is in the low half of the group order, as Bitcoin relay
+    /// policy requires.
+    pub fn is_low_s(&self) -> bool {
+        !self.s.is_high()
+    }
+
+    /// Replaces a high `s` with `N - s`. Both values verify, so anyone can
+    /// flip a signature to the other one; fixing the low one removes that
+    /// malleability.
+    pub fn normalize_s(&mut self) {
+        if !self.is_low_s() {
+            self.s = -&self.s;
+        }
+    }
+
+    /// Encodes the signature as a DER sequence of two integers.
+    pub fn der(&self) -> Vec<u8> {
+        let mut body = der_integer(&self.r);
+        body.extend(der_integer(&self.s));
+        let mut out = vec![0x30, body.len() as u8];
+        out.extend(body);
+        out
+    }
+
+    /// Decodes a DER signature, rejecting any length byte that does not
+    /// match the data that follows it and any value of `N` or more.
+    pub fn parse(der: &[u8]) -> Result<Signature, ValueError> {
+        let malformed = |reason: &str| ValueError {
+            message: format!("malformed DER signature: {}", reason),
+        };
+        if der.len() < 2 || der[0] != 0x30 {
+            return Err(malformed("missing sequence marker"));
+        }
+        if der[1] as usize != der.len() - 2 {
+            return Err(malformed("bad sequence length"));
+        }
+        let mut rest = &der[2..];
+        let mut ints = Vec::with_capacity(2);
+        for _ in 0..2 {
+            if rest.len() < 2 || rest[0] != 0x02 {
+                return Err(malformed("missing integer marker"));
+            }
+            let len = rest[1] as usize;
+            if len == 0 || rest.len() < 2 + len {
+                return Err(malformed("bad integer length"));
+            }
+            let num = UBig::from_be_bytes(&rest[2..2 + len]);
+            if num >= secp256k1::n() {
+                return Err(malformed("value not below the group order"));
+            }
+            ints.push(Scalar::new(num));
+            rest = &rest[2 + len..];
+        }
+        if !rest.is_empty() {
+            return Err(malformed("trailing bytes"));
+        }
+        let s = ints.pop().unwrap();
+        let r = ints.pop().unwrap();
+        Ok(Signature { r, s })
+    }
+
+    /// The 65-byte compact encoding used for signed messages: a header byte
+    /// `27 + recovery_id`, plus 4 if the signer's key is compressed,
+    /// followed by `r` and `s` as 32 bytes each.
+    ///
+    /// Panics if `recovery_id` is not in the range 0 to 3.
+    pub fn to_compact(&self, recovery_id: u8, compressed: bool) -> [u8; 65] {
+        assert!(recovery_id < 4, "recovery id must be in the range 0 to 3");
+        let mut out = [0u8; 65];
+        out[0] = 27 + recovery_id + if compressed { 4 } else { 0 };
+        out[1..33].copy_from_slice(&self.r.to_be_bytes());
+        out[33..].copy_from_slice(&self.s.to_be_bytes());
+        out
+    }
+
+    /// Decodes a compact signature into the signature, its recovery id and
+    /// whether the key is compressed, for use with `recover_public_key`.
+    pub fn parse_compact(bytes: &[u8]) -> Result<(Signature, u8, bool), ValueError> {
+        if bytes.len() != 65 {
+            return Err(ValueError {
+                message: format!("compact signature must be 65 bytes, got {}", bytes.len()),
+            });
+        }
+        let (recovery_id, compressed) = match bytes[0] {
+            27..=30 => (bytes[0] - 27, false),
+            31..=34 => (bytes[0] - 31, true),
+            header => {
+                return Err(ValueError {
+                    message: format!("bad compact signature header {}", header),
+                })
+            }
+        };
+        let sig = Signature {
+            r: Scalar::try_from(&bytes[1..33])?,
+            s: Scalar::try_from(&bytes[33..])?,
+        };
+        Ok((sig, recovery_id, compressed))
+    }
+}
+
+/// DER integer: minimal big-endian bytes, with a `0x00` prepended when the
+/// high bit is set so the value is not read as negative.
+fn der_integer(num: &Scalar) -> Vec<u8> {
+    let bytes = num.to_be_bytes();
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(31);
+    let mut out = vec![0x02];
+    if bytes[start] & 0x80 != 0 {
+        out.push((32 - start + 1) as u8);
+        out.push(0x00);
+    } else {
+        out.push((32 - start) as u8);
+    }
+    out.extend_from_slice(&bytes[start..]);
+    out
+}
+
+/// Checks `sig` over the message hash `z` against the public key `point`:
+/// with `u = z/s` and `v = r/s` (mod N), the signature is valid when the
+/// x coordinate of `u*G + v*P`, reduced mod N, equals `r`.
+pub fn verify(point: &Point, z: &UBig, sig: &Signature) -> bool {
+    if sig.r.is_zero() {
+        return false;
+    }
+    let s_inv = match sig.s.inverse() {
+        Some(inv) => inv,
+        None => return false,
+    };
+    let u = &Scalar::new(z.clone()) * &s_inv;
+    let v = &sig.r * &s_inv;
+    let total = Point::mul_add(u.as_ubig(), &secp256k1::g(), v.as_ubig(), point);
+    let n = secp256k1::n();
+    match total.x {
+        Some(x) => FieldElement::from_reduced(x.num, n.clone())
+            .ct_eq(&FieldElement::from_reduced(sig.r.as_ubig().clone(), n)),
+        None => false,
+    }
+}
+
+/// Like `verify`, but also rejects a high-s signature even when the
+/// curve equation holds.
+pub fn verify_strict(point: &Point, z: &UBig, sig: &Signature) -> bool {
+    sig.is_low_s() && verify(point, z, sig)
+}
+
+/// Recovers the public key that produced `sig` over `z`. Bit 0 of
+/// `recovery_id` is the parity of the nonce point's y coordinate and bit 1
+/// says its x coordinate overflowed N, i.e. was `r + N`. The key is then
+/// `r^-1 * (s*R - z*G)`.
+pub fn recover_public_key(sig: &Signature, z: &UBig, recovery_id: u8) -> Result<Point, ValueError> {
+    let n = secp256k1::n();
+    if recovery_id > 3 {
+        return Err(ValueError {
+            message: format!("recovery id {} is not in the range 0 to 3", recovery_id),
+        });
+    }
+    if sig.r.is_zero() || sig.s.is_zero() {
+        return Err(ValueError {
+            message: "signature values must be in the range 1 to N-1".to_string(),
+        });
+    }
+    let x = match recovery_id & 2 {
+        0 => sig.r.as_ubig().clone(),
+        _ => sig.r.as_ubig() + &n,
+    };
+    let x = secp256k1::s256_field(x)?;
+    let beta = secp256k1::sqrt(&(x.clone().pow(3) + secp256k1::b())).ok_or(EccError::NotOnCurve)?;
+    let y = match beta.num.bit(0) == (recovery_id & 1 == 1) {
+        true => beta,
+        false => -beta,
+    };
+    let big_r = Point::new(Some(x), Some(y), secp256k1::a(), secp256k1::b())?;
+    // r is non-zero, so it has an inverse
+    let r_inv = sig.r.inverse().unwrap();
+    let u1 = &(-Scalar::new(z.clone())) * &r_inv;
+    let u2 = &sig.s * &r_inv;
+    let point = &(&secp256k1::g() * u1.as_ubig()) + &(&big_r * u2.as_ubig());
+    match point.is_infinity() {
+        true => Err(EccError::PointAtInfinity.into()),
+        false => Ok(point),
+    }
+}
+
+/// Operations on public keys, which are plain curve points.
+pub struct PublicKey;
+
+impl PublicKey {
+    /// Sums `keys`, as needed for multisig and key aggregation. All keys
+    /// must share the first key's curve, and a sum at infinity is rejected
+    /// since it is no usable key.
+    pub fn combine(keys: &[Point]) -> Result<Point, ValueError> {
+        let first = match keys.first() {
+            Some(first) => first,
+            None => {
+                return Err(ValueError {
+                    message: "no keys to combine".to_string(),
+                })
+            }
+        };
+        let mut sum = Point::infinity(first.a.clone(), first.b.clone());
+        for key in keys {
+            if key.a != first.a || key.b != first.b {
+                return Err(ValueError {
+                    message: "keys are not on the same curve".to_string(),
+                });
+            }
+            sum = &sum + key;
+        }
+        match sum.is_infinity() {
+            true => Err(EccError::PointAtInfinity.into()),
+            false => Ok(sum),
+        }
+    }
+}
+
+/// Overwrites secret-derived bytes when the `zeroize` feature is enabled.
+fn wipe_bytes(bytes: &mut [u8]) {
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(bytes);
+    #[cfg(not(feature = "zeroize"))]
+    let _ = bytes;
+}
+
+/// Zeroes `num` when the `zeroize` feature is enabled. `UBig` exposes no
+/// limbs, so its bits are cleared from the bottom up: the limbs are then
+/// overwritten in place before the emptied buffer is released. This is best
+/// effort, since copies left behind by earlier arithmetic (reallocations,
+/// temporaries) are out of reach.
+fn wipe_ubig(num: &mut UBig) {
+    #[cfg(feature = "zeroize")]
+    for i in 0..num.bit_len() {
+        num.clear_bit(i);
+    }
+    #[cfg(not(feature = "zeroize"))]
+    let _ = num;
+}
+
+/// A secp256k1 secret key. Its public point `secret * G` is computed on
+/// the first call to `public_key` and cached; the secret is private, so
+/// the cache cannot go stale. With the `zeroize` feature the secret is
+/// wiped on drop, subject to the limits described on `wipe_ubig`.
+#[derive(Clone)]
+pub struct PrivateKey {
+    secret: Scalar,
+    point: OnceLock<Point>,
+}
+
+/// Redacts the secret, so that a key in a log line or panic message does
+/// not leak it.
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("PrivateKey")
+            .field("secret", &format_args!("<redacted>"))
+            .field("point", &self.point.get())
+            .finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        wipe_ubig(&mut self.secret.0);
+    }
+}
+
+impl PrivateKey {
+    /// Takes the secret as a plain integer so that one outside 1 to N-1 is
+    /// rejected rather than silently reduced into a different key.
+    pub fn new(secret: UBig) -> Result<PrivateKey, ValueError> {
+        if secret == ubig!(0) || secret >= secp256k1::n() {
+            return Err(ValueError {
+                message: "secret must be in the range 1 to N-1".to_string(),
+            });
+        }
+        Ok(PrivateKey {
+            secret: Scalar::new(secret),
+            point: OnceLock::new(),
+        })
+    }
+
+    pub fn secret(&self) -> &Scalar {
+        &self.secret
+    }
+
+    /// A fresh key with a secret drawn uniformly from 1 to N-1. Candidates
+    /// of 32 random bytes outside that range are discarded and redrawn
+    /// rather than reduced mod N, which would favour small secrets; with N
+    /// this close to 2^256 a redraw is almost never needed.
+    #[cfg(feature = "rand")]
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> PrivateKey {
+        let mut bytes = [0u8; 32];
+        loop {
+            rng.fill_bytes(&mut bytes);
+            let candidate = secp256k1::scalar_from_bytes(&bytes);
+            wipe_bytes(&mut bytes);
+            if let Ok(secret) = candidate {
+                return PrivateKey::new(secret).unwrap();
+            }
+        }
+    }
+
+    /// The public key `secret * G`, computed once and then borrowed.
+    pub fn public_key(&self) -> &Point {
+        self.point
+            .get_or_init(|| mul_generator(self.secret.as_ubig()))
+    }
+
+    /// Signs the message hash `z` with a deterministic RFC 6979 nonce. `s` is
+    /// always normalized to the low half of the group order.
+    pub fn sign(&self, z: &UBig) -> Signature {
+        self.sign_with_extra(z, &[])
+    }
+
+    /// Like `sign`, but mixes `extra` into the nonce derivation as the
+    /// additional data `k'` of RFC 6979 section 3.6, so the same key and
+    /// message give a different, still deterministic, signature. An empty
+    /// `extra` is exactly `sign`.
+    pub fn sign_with_extra(&self, z: &UBig, extra: &[u8]) -> Signature {
+        let mut k = Scalar::new(self.deterministic_k(z, extra));
+        let r = Scalar::new(mul_generator(k.as_ubig()).x.unwrap().num);
+        let mut k_inv = k.inverse().unwrap();
+        let s = &(&Scalar::new(z.clone()) + &(&r * &self.secret)) * &k_inv;
+        wipe_ubig(&mut k.0);
+        wipe_ubig(&mut k_inv.0);
+        let mut sig = Signature { r, s };
+        sig.normalize_s();
+        sig
+    }
+
+    /// Wallet Import Format: version byte, the 32-byte secret, an optional
+    /// `0x01` compression flag, all Base58Check encoded.
+    pub fn wif(&self, compressed: bool, testnet: bool) -> String {
+        let mut payload = vec![if testnet { 0xef } else { 0x80 }];
+        payload.extend_from_slice(&self.secret.to_be_bytes());
+        if compressed {
+            payload.push(0x01);
+        }
+        base58::encode_check(&payload)
+    }
+
+    /// Parses a WIF string, returning the key and whether it was marked as
+    /// compressed and as testnet, in that order.
+    pub fn from_wif(wif: &str) -> Result<(PrivateKey, bool, bool), ValueError> {
+        let payload = base58::decode_check(wif)?;
+        let testnet = match payload.first() {
+            Some(0x80) => false,
+            Some(0xef) => true,
+            _ => {
+                return Err(ValueError {
+                    message: "unknown WIF version byte".to_string(),
+                })
+            }
+        };
+        let compressed = match (payload.len(), payload.last()) {
+            (33, _) => false,
+            (34, Some(0x01)) => true,
+            _ => {
+                return Err(ValueError {
+                    message: "bad WIF payload length".to_string(),
+                })
+            }
+        };
+        let key = PrivateKey::new(UBig::from_be_bytes(&payload[1..33]))?;
+        Ok((key, compressed, testnet))
+    }
+
+    /// Derives the nonce for `z` per RFC 6979 section 3.2 using HMAC-SHA256.
+    fn deterministic_k(&self, z: &UBig, extra: &[u8]) -> UBig {
+        let n = secp256k1::n();
+        let mut k = [0u8; 32];
+        let mut v = [1u8; 32];
+        // bits2octets: the hash reduced mod N, as sign_with_extra reduces it
+        let z_bytes = to_bytes32(&(z % &n));
+        let mut secret_bytes = self.secret.to_be_bytes();
+        for &prefix in [0x00u8, 0x01].iter() {
+            // sized up front so no reallocation strands a copy of the secret
+            let mut data = Vec::with_capacity(97 + extra.len());
+            data.extend_from_slice(&v);
+            data.push(prefix);
+            data.extend_from_slice(&secret_bytes);
+            data.extend_from_slice(&z_bytes);
+            data.extend_from_slice(extra);
+            k = hmac_sha256(&k, &data);
+            v = hmac_sha256(&k, &v);
+            wipe_bytes(&mut data);
+        }
+        let candidate = loop {
+            v = hmac_sha256(&k, &v);
+            let candidate = UBig::from_be_bytes(&v);
+            if candidate >= ubig!(1) && candidate < n {
+                break candidate;
+            }
+            let mut data = v.to_vec();
+            data.push(0x00);
+            k = hmac_sha256(&k, &data);
+            v = hmac_sha256(&k, &v);
+        };
+        wipe_bytes(&mut secret_bytes);
+        wipe_bytes(&mut k);
+        wipe_bytes(&mut v);
+        candidate
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hash::hash256;
+    use crate::varint::encode_varint;
+
+    fn hex(s: &str) -> UBig {
+        UBig::from_str_radix(s, 16).unwrap()
+    }
+
+    fn scalar(s: &str) -> Scalar {
+        Scalar::new(hex(s))
+    }
+
+    fn textbook_key() -> Point {
+        secp256k1::s256_point(
+            hex("887387e452b8eacc4acfde10d9aaf7f6d9a0f975aabb10d006e4da568744d06c"),
+            hex("61de6d95231cd89026e286df3b6ae4a894a3378e393e93a0f45b666329a0ae34"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_verify() {
+        let point = textbook_key();
+        let z = hex("ec208baa0fc1c19f708a9ca96fdeff3ac3f230bb4a7ba4aede4942ad003c0f60");
+        let sig = Signature {
+            r: scalar("ac8d1c87e51d0d441be8b3dd5b05c8795b48875dffe00b7ffcfac23010d3a395"),
+            s: scalar("68342ceff8935ededd102dd876ffd6ba72d6a427a3edb13d26eb0781cb423c4"),
+        };
+        assert!(verify(&point, &z, &sig));
+        let z = hex("7c076ff316692a3d7eb3c3bb0f8b1488cf72e1afcd929e29307032997a838a3d");
+        let sig = Signature {
+            r: scalar("eff69ef2b1bd93a66ed5219add4fb51e11a840f404876325a1e8ffe0529a2c"),
+            s: scalar("c7207fee197d27c618aea621406f6bf5ef6fca38681d82b2f06fddbdce6feab6"),
+        };
+        assert!(verify(&point, &z, &sig));
+    }
+
+    #[test]
+    fn test_verify_rejects() {
+        let point = textbook_key();
+        let z = hex("ec208baa0fc1c19f708a9ca96fdeff3ac3f230bb4a7ba4aede4942ad003c0f60");
+        let r = scalar("ac8d1c87e51d0d441be8b3dd5b05c8795b48875dffe00b7ffcfac23010d3a395");
+        let s = scalar("68342ceff8935ededd102dd876ffd6ba72d6a427a3edb13d26eb0781cb423c4");
+        let sig = Signature {
+            r: r.clone(),
+            s: s.clone(),
+        };
+        assert!(!verify(&point, &(&z + ubig!(1)), &sig));
+        assert!(!verify(&secp256k1::g(), &z, &sig));
+        let zero_s = Signature {
+            r: r.clone(),
+            s: Scalar::new(ubig!(0)),
+        };
+        assert!(!verify(&point, &z, &zero_s));
+        let zero_r = Signature {
+            r: Scalar::new(ubig!(0)),
+            s,
+        };
+        assert!(!verify(&point, &z, &zero_r));
+    }
+
+    #[test]
+    fn test_sign() {
+        let key = PrivateKey::new(ubig!(1)).unwrap();
+        // sha256("Satoshi Nakamoto")
+        let z = hex("a0dc65ffca799873cbea0ac274015b9526505daaaed385155425f7337704883e");
+        let sig = key.sign(&z);
+        assert_eq!(
+            sig,
+            Signature {
+                r: scalar("934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8"),
+                s: scalar("2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5"),
+            }
+        );
+        assert!(verify(key.public_key(), &z, &sig));
+    }
+
+    /// A SHA-256 hash chain standing in for a seeded CSPRNG, handing out
+    /// the bytes of `script` first.
+    #[cfg(feature = "rand")]
+    struct TestRng {
+        script: Vec<u8>,
+        state: [u8; 32],
+        pos: usize,
+    }
+
+    #[cfg(feature = "rand")]
+    impl TestRng {
+        fn new(seed: &[u8], script: Vec<u8>) -> TestRng {
+            TestRng {
+                script,
+                state: crate::hash::sha256(seed),
+                pos: 0,
+            }
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    impl RngCore for TestRng {
+        fn next_u32(&mut self) -> u32 {
+            rand_core::impls::next_u32_via_fill(self)
+        }
+        fn next_u64(&mut self) -> u64 {
+            rand_core::impls::next_u64_via_fill(self)
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest.iter_mut() {
+                if !self.script.is_empty() {
+                    *byte = self.script.remove(0);
+                    continue;
+                }
+                if self.pos == 32 {
+                    self.state = crate::hash::sha256(&self.state);
+                    self.pos = 0;
+                }
+                *byte = self.state[self.pos];
+                self.pos += 1;
+            }
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    impl CryptoRng for TestRng {}
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_generate() {
+        let mut rng = TestRng::new(b"generate", Vec::new());
+        let secrets: Vec<Scalar> = (0..8)
+            .map(|_| PrivateKey::generate(&mut rng).secret().clone())
+            .collect();
+        for (i, secret) in secrets.iter().enumerate() {
+            assert!(!secret.is_zero());
+            assert!(!secrets[..i].contains(secret));
+        }
+        // the same seed gives the same keys
+        let mut again = TestRng::new(b"generate", Vec::new());
+        assert_eq!(PrivateKey::generate(&mut again).secret(), &secrets[0]);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_generate_rejects_out_of_range() {
+        // N itself and zero are both redrawn, not reduced
+        let mut script = to_bytes32(&secp256k1::n()).to_vec();
+        script.extend_from_slice(&[0u8; 32]);
+        let mut rng = TestRng::new(b"generate", script);
+        let expected = PrivateKey::generate(&mut TestRng::new(b"generate", Vec::new()));
+        assert_eq!(PrivateKey::generate(&mut rng).secret(), expected.secret());
+    }
+
+    #[test]
+    fn test_sign_deterministic() {
+        let key = PrivateKey::new(ubig!(12345)).unwrap();
+        let z = hex("969f6056aa26f7d2795fd013fe88868d09c9f6aed96965016e1936ae47060d48");
+        let sig = key.sign(&z);
+        assert_eq!(sig, key.sign(&z));
+        assert!(*sig.s.as_ubig() <= secp256k1::n() >> 1);
+        assert!(verify(key.public_key(), &z, &sig));
+        assert!(key.sign(&(&z + ubig!(1))) != sig);
+    }
+
+    #[test]
+    fn test_sign_unreduced_z() {
+        let key = PrivateKey::new(ubig!(12345)).unwrap();
+        let n = secp256k1::n();
+        // wider than 32 bytes, and between 2N and 2^256
+        for (z, reduced) in [
+            (
+                (ubig!(1) << 256) + ubig!(5),
+                (ubig!(1) << 256) % &n + ubig!(5),
+            ),
+            (&n * ubig!(2) + ubig!(1), ubig!(1)),
+        ] {
+            let sig = key.sign(&z);
+            assert_eq!(sig, key.sign(&reduced));
+            assert!(verify(key.public_key(), &reduced, &sig));
+        }
+    }
+
+    #[test]
+    fn test_normalize_s() {
+        let key = PrivateKey::new(ubig!(12345)).unwrap();
+        let z = hex("969f6056aa26f7d2795fd013fe88868d09c9f6aed96965016e1936ae47060d48");
+        let low = key.sign(&z);
+        let mut high = Signature {
+            r: low.r.clone(),
+            s: -&low.s,
+        };
+        assert!(!high.is_low_s());
+        assert!(verify(key.public_key(), &z, &high));
+        assert!(!verify_strict(key.public_key(), &z, &high));
+        high.normalize_s();
+        assert_eq!(high, low);
+        assert!(verify_strict(key.public_key(), &z, &high));
+        // already low: unchanged
+        high.normalize_s();
+        assert_eq!(high, low);
+    }
+
+    #[test]
+    fn test_sign_with_extra() {
+        let key = PrivateKey::new(ubig!(1)).unwrap();
+        let z = hex("a0dc65ffca799873cbea0ac274015b9526505daaaed385155425f7337704883e");
+        assert_eq!(key.sign_with_extra(&z, &[]), key.sign(&z));
+        let with_ones = key.sign_with_extra(&z, &[1u8; 32]);
+        assert_eq!(
+            with_ones,
+            Signature {
+                r: scalar("bb6cf569458d507451271380d2863dad30355387836d5c3287a4efbd5ed1ad8e"),
+                s: scalar("4bb4b7899e803f760fe89027e55f5d93768983d6e28af4b5722f6226b345380e"),
+            }
+        );
+        let with_text = key.sign_with_extra(&z, b"wallet entropy");
+        assert_eq!(
+            with_text.r,
+            scalar("aa06c48066f7818fe3c1f055d28c501ba54012e07114bea10c582597b4173851")
+        );
+        for sig in [&with_ones, &with_text].iter() {
+            assert!(*sig != &key.sign(&z));
+            assert!(verify(key.public_key(), &z, sig));
+        }
+        assert!(with_ones != with_text);
+        assert_eq!(key.sign_with_extra(&z, b"wallet entropy"), with_text);
+    }
+
+    #[test]
+    fn test_public_key_cached() {
+        let key = PrivateKey::new(ubig!(12345)).unwrap();
+        let first = key.public_key();
+        let second = key.public_key();
+        // the second call borrows the cached point rather than recomputing
+        assert!(std::ptr::eq(first, second));
+        assert_eq!(*first, secp256k1::g().scalar_mul(&ubig!(12345)));
+        // a clone carries the cache along
+        assert_eq!(key.clone().public_key(), first);
+        // the cache is thread-safe, so keys can be shared across threads
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+        assert_send_sync(&key);
+        let shared = std::sync::Arc::new(key.clone());
+        let remote = std::sync::Arc::clone(&shared);
+        let point = std::thread::spawn(move || remote.public_key().clone())
+            .join()
+            .unwrap();
+        assert_eq!(&point, shared.public_key());
+    }
+
+    #[test]
+    fn test_debug_redacts_secret() {
+        let key = PrivateKey::new(ubig!(0xdeadbeef)).unwrap();
+        let debug = format!("{:?}", key);
+        assert!(debug.contains("secret: <redacted>"), "{}", debug);
+        assert!(!debug.contains("deadbeef") && !debug.contains("3735928559"));
+    }
+
+    #[test]
+    fn test_private_key_range() {
+        assert!(PrivateKey::new(ubig!(0)).is_err());
+        assert!(PrivateKey::new(secp256k1::n()).is_err());
+    }
+
+    #[test]
+    fn test_der() {
+        let sig = Signature {
+            r: scalar("37206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c6"),
+            s: scalar("8ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec"),
+        };
+        let der = sig.der();
+        assert_eq!(
+            crate::hex::encode(&der),
+            "3045022037206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c6\
+             0221008ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec"
+        );
+        assert_eq!(Signature::parse(&der).unwrap(), sig);
+        let small = Signature {
+            r: Scalar::new(ubig!(1)),
+            s: Scalar::new(ubig!(0x80)),
+        };
+        assert_eq!(crate::hex::encode(&small.der()), "300702010102020080");
+        assert_eq!(Signature::parse(&small.der()).unwrap(), small);
+    }
+
+    #[test]
+    fn test_der_malformed() {
+        let sig = Signature {
+            r: scalar("37206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c6"),
+            s: scalar("8ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec"),
+        };
+        let der = sig.der();
+        let mut bad = der.clone();
+        bad[1] += 1;
+        assert!(Signature::parse(&bad).is_err());
+        bad = der.clone();
+        // r claims more bytes than the sequence holds
+        bad[3] = 0x40;
+        assert!(Signature::parse(&bad).is_err());
+        bad = der.clone();
+        bad[0] = 0x31;
+        assert!(Signature::parse(&bad).is_err());
+        assert!(Signature::parse(&der[..der.len() - 1]).is_err());
+        assert!(Signature::parse(&[]).is_err());
+        // r = N would verify like r = 0 if it were reduced
+        let mut big_r = vec![0x30, 0x26, 0x02, 0x21, 0x00];
+        big_r.extend_from_slice(&to_bytes32(&secp256k1::n()));
+        big_r.extend_from_slice(&[0x02, 0x01, 0x01]);
+        assert_eq!(
+            Signature::parse(&big_r).unwrap_err().message,
+            "malformed DER signature: value not below the group order"
+        );
+        big_r[36] -= 1;
+        assert!(Signature::parse(&big_r).is_ok());
+    }
+
+    #[test]
+    fn test_wif() {
+        let cases = [
+            (
+                ubig!(5003),
+                true,
+                true,
+                "cMahea7zqjxrtgAbB7LSGbcQUr1uX1ojuat9jZodMN8rFTv2sfUK",
+            ),
+            (
+                ubig!(2021).pow(5),
+                false,
+                true,
+                "91avARGdfge8E4tZfYLoxeJ5sGBdNJQH4kvjpWAxgzczjbCwxic",
+            ),
+            (
+                ubig!(0x54321deadbeef),
+                true,
+                false,
+                "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgiuQJv1h8Ytr2S53a",
+            ),
+            (
+                ubig!(1),
+                false,
+                false,
+                "5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf",
+            ),
+        ];
+        for (secret, compressed, testnet, wif) in cases.iter() {
+            let key = PrivateKey::new(secret.clone()).unwrap();
+            assert_eq!(key.wif(*compressed, *testnet), *wif);
+            let (parsed, parsed_compressed, parsed_testnet) = PrivateKey::from_wif(wif).unwrap();
+            assert_eq!(parsed.secret().as_ubig(), secret);
+            assert_eq!(parsed_compressed, *compressed);
+            assert_eq!(parsed_testnet, *testnet);
+        }
+    }
+
+    #[test]
+    fn test_from_wif_rejects() {
+        // a P2PKH address has a valid checksum but the wrong version byte
+        assert!(PrivateKey::from_wif("1BoatSLRHtKNngkdXEeobR76b53LETtpyT").is_err());
+        assert!(
+            PrivateKey::from_wif("5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDg").is_err()
+        );
+    }
+
+    #[test]
+    fn test_recover_public_key() {
+        let key = PrivateKey::new(ubig!(12345)).unwrap();
+        let z = hex("969f6056aa26f7d2795fd013fe88868d09c9f6aed96965016e1936ae47060d48");
+        let sig = key.sign(&z);
+        let candidates = [0, 1]
+            .iter()
+            .map(|id| recover_public_key(&sig, &z, *id).unwrap())
+            .collect::<Vec<Point>>();
+        // exactly one parity gives back the signer; both keys verify
+        assert!((candidates[0] == *key.public_key()) != (candidates[1] == *key.public_key()));
+        for point in candidates.iter() {
+            assert!(verify(point, &z, &sig));
+        }
+
+        let point = textbook_key();
+        let z = hex("ec208baa0fc1c19f708a9ca96fdeff3ac3f230bb4a7ba4aede4942ad003c0f60");
+        let sig = Signature {
+            r: scalar("ac8d1c87e51d0d441be8b3dd5b05c8795b48875dffe00b7ffcfac23010d3a395"),
+            s: scalar("68342ceff8935ededd102dd876ffd6ba72d6a427a3edb13d26eb0781cb423c4"),
+        };
+        assert!((0..2).any(|id| recover_public_key(&sig, &z, id).unwrap() == point));
+
+        // x = N + 2 lies on the curve, so r = 2 can come from an overflowed R
+        let sig = Signature {
+            r: Scalar::new(ubig!(2)),
+            s: Scalar::new(ubig!(3)),
+        };
+        for id in 2..4 {
+            let point = recover_public_key(&sig, &z, id).unwrap();
+            assert!(verify(&point, &z, &sig));
+        }
+    }
+
+    #[test]
+    fn test_compact() {
+        // the signed-message digest of "hello"
+        let message = b"hello";
+        let mut preimage = b"\x18Bitcoin Signed Message:\n".to_vec();
+        preimage.extend(encode_varint(message.len() as u64));
+        preimage.extend_from_slice(message);
+        let z = UBig::from_be_bytes(&hash256(&preimage));
+        let key = PrivateKey::new(ubig!(12345)).unwrap();
+        let sig = key.sign(&z);
+        let recovery_id = (0..4)
+            .find(|id| recover_public_key(&sig, &z, *id).ok().as_ref() == Some(key.public_key()))
+            .unwrap();
+        let compact = sig.to_compact(recovery_id, true);
+        assert_eq!(compact[0], 31 + recovery_id);
+
+        let (parsed, parsed_id, compressed) = Signature::parse_compact(&compact).unwrap();
+        assert_eq!(parsed, sig);
+        assert!(compressed);
+        let point = recover_public_key(&parsed, &z, parsed_id).unwrap();
+        assert_eq!(
+            point.address(compressed, false).unwrap(),
+            "12vieiAHxBe4qCUrwvfb2kRkDuc8kQ2VZ2"
+        );
+
+        let (_, _, compressed) =
+            Signature::parse_compact(&sig.to_compact(recovery_id, false)).unwrap();
+        assert!(!compressed);
+        assert!(Signature::parse_compact(&compact[..64]).is_err());
+        let mut bad_header = compact;
+        bad_header[0] = 35;
+        assert!(Signature::parse_compact(&bad_header).is_err());
+    }
+
+    #[test]
+    fn test_recover_public_key_rejects() {
+        let z = ubig!(1);
+        let sig = Signature {
+            r: Scalar::new(ubig!(1)),
+            s: Scalar::new(ubig!(1)),
+        };
+        assert!(recover_public_key(&sig, &z, 4).is_err());
+        // x = N + 1 is not on the curve
+        assert!(recover_public_key(&sig, &z, 2).is_err());
+        // x = r + N overflows the field prime
+        let overflow = Signature {
+            r: Scalar::new(secp256k1::p() - secp256k1::n()),
+            s: Scalar::new(ubig!(1)),
+        };
+        assert!(recover_public_key(&overflow, &z, 2).is_err());
+        let zero_r = Signature {
+            r: Scalar::new(ubig!(0)),
+            s: Scalar::new(ubig!(1)),
+        };
+        assert!(recover_public_key(&zero_r, &z, 0).is_err());
+    }
+
+    #[test]
+    fn test_combine() {
+        let (a, b) = (ubig!(12345), hex("deadbeef12345678"));
+        let g = secp256k1::g();
+        let sum = PublicKey::combine(&[&g * &a, &g * &b]).unwrap();
+        assert_eq!(sum, &g * &(&a + &b));
+        assert_eq!(PublicKey::combine(std::slice::from_ref(&g)).unwrap(), g);
+    }
+
+    #[test]
+    fn test_combine_rejects() {
+        let g = secp256k1::g();
+        assert!(PublicKey::combine(&[]).is_err());
+        assert!(PublicKey::combine(&[g.clone(), -&g]).is_err());
+        let prime = ubig!(223);
+        let other = Point::new(
+            Some(FieldElement::from_u64(47, prime.clone())),
+            Some(FieldElement::from_u64(71, prime.clone())),
+            FieldElement::from_u64(0, prime.clone()),
+            FieldElement::from_u64(7, prime),
+        )
+        .unwrap();
+        assert!(PublicKey::combine(&[g, other]).is_err());
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_wipe() {
+        let mut num = hex("e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35");
+        wipe_ubig(&mut num);
+        assert_eq!(num, ubig!(0));
+        let mut bytes = [0xffu8; 32];
+        wipe_bytes(&mut bytes);
+        assert_eq!(bytes, [0u8; 32]);
+        // keys still sign, clone and drop as usual
+        let key = PrivateKey::new(ubig!(1)).unwrap();
+        let z = hex("a0dc65ffca799873cbea0ac274015b9526505daaaed385155425f7337704883e");
+        let copy = key.clone();
+        drop(key);
+        assert!(verify(copy.public_key(), &z, &copy.sign(&z)));
+    }
+}
@@ -1,4 +1,8 @@
-use std::fmt;
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+use core::fmt;
+use ibig::{ubig, UBig};
+
 #[derive(Debug, Clone)]
 pub struct ValueError {
     pub message: String,
@@ -10,4 +14,64 @@ impl fmt::Display for ValueError {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for ValueError {}
+
+/// Failures of the field and curve primitives, distinguishable by kind.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EccError {
+    NotInFieldRange { num: UBig, prime: UBig },
+    PrimeMismatch { left: UBig, right: UBig },
+    NotOnCurve,
+    NoInverse,
+    PointAtInfinity,
+}
+
+impl fmt::Display for EccError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            EccError::NotInFieldRange { num, prime } => {
+                write!(
+                    f,
+                    "num {} not in field range 0 to {}",
+                    num,
+                    prime - ubig!(1)
+                )
+            }
+            EccError::PrimeMismatch { left, right } => write!(
+                f,
+                "cannot combine elements of different fields: prime {} and prime {}",
+                left, right
+            ),
+            EccError::NotOnCurve => write!(f, "point is not on the curve"),
+            EccError::NoInverse => write!(f, "element has no multiplicative inverse"),
+            EccError::PointAtInfinity => write!(f, "operation undefined for the point at infinity"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EccError {}
+
+impl From<EccError> for ValueError {
+    fn from(err: EccError) -> ValueError {
+        ValueError {
+            message: err.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_into_value_error() {
+        let err = EccError::NotInFieldRange {
+            num: ubig!(13),
+            prime: ubig!(13),
+        };
+        let value_err: ValueError = err.into();
+        assert_eq!(value_err.message, "num 13 not in field range 0 to 12");
+    }
+}
@@ -0,0 +1,367 @@
+use crate::ecc::{to_bytes32, FieldElement, FieldElementOps};
+use crate::errors::EccError;
+use crate::secp256k1;
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+use ibig::UBig;
+
+/// The secp256k1 prime is `2^256 - C`, so anything at or above `2^256` can
+/// be folded back down by multiplying the overflow by `C`.
+const C: u64 = 0x1000003d1;
+
+/// Little-endian limbs of the secp256k1 prime.
+const P: [u64; 4] = [
+    0xfffffffefffffc2f,
+    0xffffffffffffffff,
+    0xffffffffffffffff,
+    0xffffffffffffffff,
+];
+
+/// Element of the secp256k1 base field held in four stack-allocated 64-bit
+/// limbs, always fully reduced. Unlike `FieldElement` it is `Copy` and never
+/// allocates, at the price of being tied to a single prime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Fp256([u64; 4]);
+
+impl Fp256 {
+    pub const ZERO: Fp256 = Fp256([0, 0, 0, 0]);
+    pub const ONE: Fp256 = Fp256([1, 0, 0, 0]);
+
+    pub fn new(num: UBig) -> Result<Fp256, EccError> {
+        match num >= secp256k1::p() {
+            true => Err(EccError::NotInFieldRange {
+                num,
+                prime: secp256k1::p(),
+            }),
+            false => Ok(Fp256::from_be_bytes(&to_bytes32(&num))),
+        }
+    }
+
+    /// Reads a big-endian 32-byte value, reducing it modulo the prime.
+    pub fn from_be_bytes(bytes: &[u8; 32]) -> Fp256 {
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(&bytes[24 - 8 * i..32 - 8 * i]);
+            *limb = u64::from_be_bytes(word);
+        }
+        Fp256(reduce_once(limbs, false))
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, limb) in self.0.iter().enumerate() {
+            out[24 - 8 * i..32 - 8 * i].copy_from_slice(&limb.to_be_bytes());
+        }
+        out
+    }
+
+    pub fn to_ubig(&self) -> UBig {
+        UBig::from_be_bytes(&self.to_be_bytes())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    pub fn pow(self, power: i128) -> Fp256 {
+        // same reduction as FieldElement::pow: the exponent only matters
+        // mod p-1, and a negative one is wrapped up into range
+        let magnitude = power.unsigned_abs();
+        let exp = [magnitude as u64, (magnitude >> 64) as u64, 0, 0];
+        match power < 0 {
+            true => {
+                let p_minus_one = [P[0] - 1, P[1], P[2], P[3]];
+                self.pow_limbs(&sub_limbs(&p_minus_one, &exp).0)
+            }
+            false => self.pow_limbs(&exp),
+        }
+    }
+
+    pub fn inverse(&self) -> Result<Fp256, EccError> {
+        match self.is_zero() {
+            true => Err(EccError::NoInverse),
+            false => Ok(self.pow_limbs(&[P[0] - 2, P[1], P[2], P[3]])),
+        }
+    }
+
+    pub fn sqrt(&self) -> Option<Fp256> {
+        // p = 3 mod 4, so a root (if one exists) is self^((p+1)/4)
+        let p_plus_one = [P[0] + 1, P[1], P[2], P[3]];
+        let exp = [
+            (p_plus_one[0] >> 2) | (p_plus_one[1] << 62),
+            (p_plus_one[1] >> 2) | (p_plus_one[2] << 62),
+            (p_plus_one[2] >> 2) | (p_plus_one[3] << 62),
+            p_plus_one[3] >> 2,
+        ];
+        let root = self.pow_limbs(&exp);
+        match root * root == *self {
+            true => Some(root),
+            false => None,
+        }
+    }
+
+    fn pow_limbs(self, exp: &[u64; 4]) -> Fp256 {
+        let mut result = Fp256::ONE;
+        for i in (0..256).rev() {
+            result = result * result;
+            if (exp[i / 64] >> (i % 64)) & 1 == 1 {
+                result *= self;
+            }
+        }
+        result
+    }
+}
+
+/// Adds with carry, returning the sum limbs and the carry out of the top.
+fn add_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut out = [0u64; 4];
+    let mut carry = false;
+    for i in 0..4 {
+        let (sum, c1) = a[i].overflowing_add(b[i]);
+        let (sum, c2) = sum.overflowing_add(carry as u64);
+        out[i] = sum;
+        carry = c1 || c2;
+    }
+    (out, carry)
+}
+
+/// Subtracts with borrow, returning the difference limbs and the borrow out
+/// of the top.
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut out = [0u64; 4];
+    let mut borrow = false;
+    for i in 0..4 {
+        let (diff, b1) = a[i].overflowing_sub(b[i]);
+        let (diff, b2) = diff.overflowing_sub(borrow as u64);
+        out[i] = diff;
+        borrow = b1 || b2;
+    }
+    (out, borrow)
+}
+
+/// Brings a value below `2p` (with `overflow` standing for a set bit 256)
+/// into `[0, p)`. Subtracting `p` is the same as adding `C` mod `2^256`.
+fn reduce_once(limbs: [u64; 4], overflow: bool) -> [u64; 4] {
+    let (_, below_p) = sub_limbs(&limbs, &P);
+    match overflow || !below_p {
+        true => add_limbs(&limbs, &[C, 0, 0, 0]).0,
+        false => limbs,
+    }
+}
+
+/// Reduces a 512-bit product by folding the high half in twice as `hi * C`.
+fn reduce_wide(wide: [u64; 8]) -> [u64; 4] {
+    let mut out = [0u64; 4];
+    let mut carry: u128 = 0;
+    for i in 0..4 {
+        let t = u128::from(wide[i]) + u128::from(wide[i + 4]) * u128::from(C) + carry;
+        out[i] = t as u64;
+        carry = t >> 64;
+    }
+    // carry is now below 2^34, so carry * C fits easily in a u128
+    let mut fold = carry * u128::from(C);
+    for limb in out.iter_mut() {
+        let t = u128::from(*limb) + fold;
+        *limb = t as u64;
+        fold = t >> 64;
+    }
+    reduce_once(out, fold != 0)
+}
+
+impl Add for Fp256 {
+    type Output = Fp256;
+    fn add(self, rhs: Fp256) -> Fp256 {
+        let (sum, carry) = add_limbs(&self.0, &rhs.0);
+        Fp256(reduce_once(sum, carry))
+    }
+}
+
+impl AddAssign for Fp256 {
+    fn add_assign(&mut self, rhs: Fp256) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Fp256 {
+    type Output = Fp256;
+    fn sub(self, rhs: Fp256) -> Fp256 {
+        // on borrow the result wrapped by 2^256; adding p back is the same
+        // as subtracting C
+        let (diff, borrow) = sub_limbs(&self.0, &rhs.0);
+        match borrow {
+            true => Fp256(sub_limbs(&diff, &[C, 0, 0, 0]).0),
+            false => Fp256(diff),
+        }
+    }
+}
+
+impl SubAssign for Fp256 {
+    fn sub_assign(&mut self, rhs: Fp256) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Fp256 {
+    type Output = Fp256;
+    fn neg(self) -> Fp256 {
+        Fp256::ZERO - self
+    }
+}
+
+impl Mul for Fp256 {
+    type Output = Fp256;
+    fn mul(self, rhs: Fp256) -> Fp256 {
+        let mut wide = [0u64; 8];
+        for i in 0..4 {
+            let mut carry: u128 = 0;
+            for j in 0..4 {
+                let t =
+                    u128::from(wide[i + j]) + u128::from(self.0[i]) * u128::from(rhs.0[j]) + carry;
+                wide[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            wide[i + 4] = carry as u64;
+        }
+        Fp256(reduce_wide(wide))
+    }
+}
+
+impl MulAssign for Fp256 {
+    fn mul_assign(&mut self, rhs: Fp256) {
+        *self = *self * rhs;
+    }
+}
+
+impl Div for Fp256 {
+    type Output = Fp256;
+    fn div(self, rhs: Fp256) -> Fp256 {
+        // like FieldElement, dividing by zero yields zero rather than panicking
+        self * rhs.pow_limbs(&[P[0] - 2, P[1], P[2], P[3]])
+    }
+}
+
+impl fmt::Display for Fp256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "Fp256({})", self.to_ubig())
+    }
+}
+
+impl From<Fp256> for FieldElement {
+    fn from(value: Fp256) -> FieldElement {
+        FieldElement::from_reduced(value.to_ubig(), secp256k1::p())
+    }
+}
+
+impl TryFrom<&FieldElement> for Fp256 {
+    type Error = EccError;
+    fn try_from(value: &FieldElement) -> Result<Fp256, EccError> {
+        match value.prime == secp256k1::p() {
+            true => Fp256::new(value.num.clone()),
+            false => Err(EccError::PrimeMismatch {
+                left: value.prime.clone(),
+                right: secp256k1::p(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ibig::ubig;
+
+    fn hex(s: &str) -> UBig {
+        UBig::from_str_radix(s, 16).unwrap()
+    }
+
+    fn samples() -> Vec<UBig> {
+        let p = secp256k1::p();
+        vec![
+            ubig!(0),
+            ubig!(1),
+            ubig!(7),
+            &p - ubig!(1),
+            &p - ubig!(2),
+            (ubig!(1) << 255) + ubig!(12345),
+            hex("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+            hex("483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"),
+            hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc00"),
+        ]
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(Fp256::new(ubig!(0)).unwrap(), Fp256::ZERO);
+        assert_eq!(Fp256::new(ubig!(1)).unwrap(), Fp256::ONE);
+        assert!(Fp256::new(secp256k1::p()).is_err());
+        for n in samples() {
+            assert_eq!(Fp256::new(n.clone()).unwrap().to_ubig(), n);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_matches_field_element() {
+        let p = secp256k1::p();
+        for a in samples() {
+            for b in samples() {
+                let (x, y) = (
+                    Fp256::new(a.clone()).unwrap(),
+                    Fp256::new(b.clone()).unwrap(),
+                );
+                let (fx, fy) = (
+                    FieldElement::new(a.clone(), p.clone()).unwrap(),
+                    FieldElement::new(b.clone(), p.clone()).unwrap(),
+                );
+                assert_eq!(FieldElement::from(x + y), &fx + &fy);
+                assert_eq!(FieldElement::from(x - y), &fx - &fy);
+                assert_eq!(FieldElement::from(x * y), &fx * &fy);
+                if !y.is_zero() {
+                    assert_eq!(FieldElement::from(x / y), &fx / &fy);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_neg() {
+        for n in samples() {
+            let x = Fp256::new(n).unwrap();
+            assert_eq!(x + -x, Fp256::ZERO);
+        }
+        assert_eq!(-Fp256::ZERO, Fp256::ZERO);
+    }
+
+    #[test]
+    fn test_pow_and_inverse() {
+        let x = Fp256::new(ubig!(3)).unwrap();
+        assert_eq!(x.pow(0), Fp256::ONE);
+        assert_eq!(x.pow(5), Fp256::new(ubig!(243)).unwrap());
+        assert_eq!(x.pow(-1) * x, Fp256::ONE);
+        assert_eq!(x.pow(-3) * x.pow(3), Fp256::ONE);
+        assert_eq!(x.inverse().unwrap(), x.pow(-1));
+        assert_eq!(Fp256::ZERO.inverse(), Err(EccError::NoInverse));
+    }
+
+    #[test]
+    fn test_sqrt() {
+        let gx = Fp256::new(secp256k1::g().x.unwrap().num).unwrap();
+        let gy = Fp256::new(secp256k1::g().y.unwrap().num).unwrap();
+        let rhs = gx * gx * gx + Fp256::new(ubig!(7)).unwrap();
+        let root = rhs.sqrt().unwrap();
+        assert!(root == gy || root == -gy);
+        // -1 is not a square since p = 3 mod 4
+        assert_eq!((-Fp256::ONE).sqrt(), None);
+        assert_eq!(Fp256::ZERO.sqrt(), Some(Fp256::ZERO));
+    }
+
+    #[test]
+    fn test_field_element_conversion() {
+        let fe = secp256k1::s256_field(ubig!(42)).unwrap();
+        let x = Fp256::try_from(&fe).unwrap();
+        assert_eq!(FieldElement::from(x), fe);
+        let other = FieldElement::new(ubig!(4), ubig!(31)).unwrap();
+        assert!(Fp256::try_from(&other).is_err());
+    }
+}
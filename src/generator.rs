@@ -0,0 +1,160 @@
+use crate::ecc::{batch_inverse, FieldElement};
+use crate::jacobian::JacobianPoint;
+use crate::point::{Point, PointOps};
+use crate::secp256k1;
+use ibig::UBig;
+
+const WINDOW_BITS: usize = 4;
+const WINDOW_SIZE: usize = 1 << WINDOW_BITS;
+
+/// Precomputed multiples of a fixed base point for fast scalar
+/// multiplication: window `i` holds `j * 16^i * base` for `j` in 1..16, so a
+/// product is one table addition per 4-bit digit of the scalar and needs no
+/// doublings at all.
+#[derive(Clone, Debug)]
+pub struct GeneratorTable {
+    windows: Vec<Vec<JacobianPoint>>,
+}
+
+impl GeneratorTable {
+    /// Builds the table for scalars up to the bit length of the base's field
+    /// prime. Every entry is brought back to `Z = 1` with one batched
+    /// inversion, so none may be at infinity; a base of large prime order
+    /// such as `G` guarantees that.
+    ///
+    /// Panics if an entry is at infinity.
+    pub fn new(base: &Point) -> GeneratorTable {
+        let bits = base.a.prime.bit_len();
+        let num_windows = bits.div_ceil(WINDOW_BITS);
+        let mut points = Vec::with_capacity(num_windows * (WINDOW_SIZE - 1));
+        let mut window_base = base.to_jacobian();
+        for _ in 0..num_windows {
+            let mut multiple = window_base.clone();
+            for _ in 1..WINDOW_SIZE {
+                points.push(multiple.clone());
+                multiple = &multiple + &window_base;
+            }
+            // multiple is now 16 * window_base
+            window_base = multiple;
+        }
+        let z_invs = batch_inverse(&points.iter().map(|p| p.z.clone()).collect::<Vec<_>>())
+            .expect("table entry at infinity");
+        let normalized = points
+            .iter()
+            .zip(z_invs.iter())
+            .map(|(p, z_inv)| normalize(p, z_inv))
+            .collect::<Vec<_>>();
+        GeneratorTable {
+            windows: normalized
+                .chunks(WINDOW_SIZE - 1)
+                .map(|chunk| chunk.to_vec())
+                .collect(),
+        }
+    }
+
+    /// Multiplies the base by `k`. Every window costs exactly one addition:
+    /// a zero digit adds into a throwaway accumulator instead of being
+    /// skipped. The entry is picked by a masked scan over the whole window
+    /// and the accumulators are exchanged with `conditional_swap`, so no
+    /// branch or index depends on a digit. As with `scalar_mul_ct`, Jacobian
+    /// addition still returns early while the accumulator is at infinity,
+    /// which reveals how many low digits are zero, and `UBig` arithmetic is
+    /// not constant-time.
+    ///
+    /// Panics if `k` is wider than the table.
+    pub fn mul(&self, k: &UBig) -> Point {
+        assert!(
+            k.bit_len() <= self.windows.len() * WINDOW_BITS,
+            "scalar too large for table"
+        );
+        let first = &self.windows[0][0];
+        let mut acc = JacobianPoint::infinity(first.a.clone(), first.b.clone());
+        let mut dummy = first.clone();
+        for (i, window) in self.windows.iter().enumerate() {
+            let digit = (0..WINDOW_BITS).fold(0, |digit, bit| {
+                digit | ((k.bit(i * WINDOW_BITS + bit) as usize) << bit)
+            });
+            // a zero digit leaves the first entry, which goes to the dummy
+            let mut entry = window[0].clone();
+            for (j, candidate) in window.iter().enumerate().skip(1) {
+                entry.conditional_swap(&mut candidate.clone(), j + 1 == digit);
+            }
+            acc.conditional_swap(&mut dummy, digit == 0);
+            acc = &acc + &entry;
+            acc.conditional_swap(&mut dummy, digit == 0);
+        }
+        acc.to_affine()
+    }
+}
+
+fn normalize(point: &JacobianPoint, z_inv: &FieldElement) -> JacobianPoint {
+    let z_inv2 = z_inv * z_inv;
+    let z_inv3 = &z_inv2 * z_inv;
+    JacobianPoint {
+        x: &point.x * &z_inv2,
+        y: &point.y * &z_inv3,
+        z: &point.z * z_inv,
+        a: point.a.clone(),
+        b: point.b.clone(),
+    }
+}
+
+thread_local! {
+    static G_TABLE: GeneratorTable = GeneratorTable::new(&secp256k1::g());
+}
+
+/// `k * G` on secp256k1 through a table built once per thread.
+pub fn mul_generator(k: &UBig) -> Point {
+    G_TABLE.with(|table| table.mul(k))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecc::FieldElementOps;
+    use crate::hash::sha256;
+    use ibig::ubig;
+
+    #[test]
+    fn test_mul_matches_scalar_mul() {
+        let g = secp256k1::g();
+        let mut scalars = vec![
+            ubig!(1),
+            ubig!(15),
+            ubig!(16),
+            ubig!(0x1000000),
+            secp256k1::n() - ubig!(1),
+        ];
+        // pseudo-random scalars from a hash chain
+        let mut seed = sha256(b"generator table");
+        for _ in 0..8 {
+            scalars.push(UBig::from_be_bytes(&seed) % secp256k1::n());
+            seed = sha256(&seed);
+        }
+        for k in scalars.iter() {
+            assert_eq!(mul_generator(k), g.scalar_mul(k));
+        }
+        assert!(mul_generator(&ubig!(0)).is_infinity());
+        assert!(mul_generator(&secp256k1::n()).is_infinity());
+    }
+
+    #[test]
+    fn test_small_curve() {
+        let prime = ubig!(223);
+        let fe = |num: u64| FieldElement::from_u64(num, prime.clone());
+        let base = Point::new(Some(fe(47)), Some(fe(71)), fe(0), fe(7)).unwrap();
+        let table = GeneratorTable::new(&base);
+        // (47, 71) has order 21, coprime to 16, so no entry is at infinity;
+        // the 8-bit prime gives two windows
+        for k in 0..256u32 {
+            let k = UBig::from(k);
+            assert_eq!(table.mul(&k), base.scalar_mul(&k));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "scalar too large for table")]
+    fn test_mul_too_large() {
+        mul_generator(&(ubig!(1) << 256));
+    }
+}
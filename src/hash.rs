@@ -0,0 +1,513 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const SHA256_H: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const SHA512_K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+const SHA512_H: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+const RIPEMD160_H: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+// message word order, rotation amounts and constants for the left and
+// right RIPEMD-160 lines
+const RIPEMD160_R: [usize; 80] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5,
+    2, 14, 11, 8, 3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12, 1, 9, 11, 10, 0, 8, 12, 4,
+    13, 3, 7, 15, 14, 5, 6, 2, 4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+];
+const RIPEMD160_R_PRIME: [usize; 80] = [
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12, 6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12,
+    4, 9, 1, 2, 15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13, 8, 6, 4, 1, 3, 11, 15, 0, 5,
+    12, 2, 13, 9, 7, 10, 14, 12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+];
+const RIPEMD160_S: [u32; 80] = [
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8, 7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15,
+    9, 11, 7, 13, 12, 11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5, 11, 12, 14, 15, 14,
+    15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12, 9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6,
+];
+const RIPEMD160_S_PRIME: [u32; 80] = [
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6, 9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12,
+    7, 6, 15, 13, 11, 9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5, 15, 5, 8, 11, 14, 14,
+    6, 14, 6, 9, 12, 9, 12, 5, 15, 8, 8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11,
+];
+const RIPEMD160_K: [u32; 5] = [0x00000000, 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xa953fd4e];
+const RIPEMD160_K_PRIME: [u32; 5] = [0x50a28be6, 0x5c4dd124, 0x6d703ef3, 0x7a6d76e9, 0x00000000];
+
+/// Merkle-Damgard padding: a `0x80` byte, zeros, then `length` (the encoded
+/// message bit length) so the result is a multiple of `block` bytes.
+fn md_pad(data: &[u8], block: usize, length: &[u8]) -> Vec<u8> {
+    let mut padded = data.to_vec();
+    padded.push(0x80);
+    while padded.len() % block != block - length.len() {
+        padded.push(0);
+    }
+    padded.extend_from_slice(length);
+    padded
+}
+
+fn sha256_compress(state: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 64];
+    for (i, word) in block.chunks(4).enumerate() {
+        w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(SHA256_K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h].iter()) {
+        *s = s.wrapping_add(*v);
+    }
+}
+
+/// SHA-256 digest of `data`.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    sha256_resume(SHA256_H, 0, data)
+}
+
+/// Finishes a SHA-256 whose first `prefix_len` bytes (a multiple of the
+/// block size) have already been compressed into `state`.
+fn sha256_resume(mut state: [u32; 8], prefix_len: u64, data: &[u8]) -> [u8; 32] {
+    let bit_len = (prefix_len + data.len() as u64) * 8;
+    for block in md_pad(data, 64, &bit_len.to_be_bytes()).chunks(64) {
+        sha256_compress(&mut state, block);
+    }
+    let mut out = [0u8; 32];
+    for (chunk, word) in out.chunks_mut(4).zip(state.iter()) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+/// SHA-256 applied twice, as used for txids, block hashes and checksums.
+pub fn hash256(data: &[u8]) -> [u8; 32] {
+    sha256(&sha256(data))
+}
+
+fn sha512_compress(state: &mut [u64; 8], block: &[u8]) {
+    let mut w = [0u64; 80];
+    for (i, word) in block.chunks(8).enumerate() {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(word);
+        w[i] = u64::from_be_bytes(buf);
+    }
+    for i in 16..80 {
+        let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+        let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for i in 0..80 {
+        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(SHA512_K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h].iter()) {
+        *s = s.wrapping_add(*v);
+    }
+}
+
+/// SHA-512 digest of `data`, the hash underlying BIP32 and BIP39.
+pub fn sha512(data: &[u8]) -> [u8; 64] {
+    let mut state = SHA512_H;
+    let bit_len = (data.len() as u128) * 8;
+    for block in md_pad(data, 128, &bit_len.to_be_bytes()).chunks(128) {
+        sha512_compress(&mut state, block);
+    }
+    let mut out = [0u8; 64];
+    for (chunk, word) in out.chunks_mut(8).zip(state.iter()) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+fn ripemd160_f(j: usize, x: u32, y: u32, z: u32) -> u32 {
+    match j / 16 {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        3 => (x & z) | (y & !z),
+        _ => x ^ (y | !z),
+    }
+}
+
+fn ripemd160_compress(state: &mut [u32; 5], block: &[u8]) {
+    let mut x = [0u32; 16];
+    for (i, word) in block.chunks(4).enumerate() {
+        x[i] = u32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    let [mut al, mut bl, mut cl, mut dl, mut el] = *state;
+    let [mut ar, mut br, mut cr, mut dr, mut er] = *state;
+    for j in 0..80 {
+        let t = al
+            .wrapping_add(ripemd160_f(j, bl, cl, dl))
+            .wrapping_add(x[RIPEMD160_R[j]])
+            .wrapping_add(RIPEMD160_K[j / 16])
+            .rotate_left(RIPEMD160_S[j])
+            .wrapping_add(el);
+        al = el;
+        el = dl;
+        dl = cl.rotate_left(10);
+        cl = bl;
+        bl = t;
+        let t = ar
+            .wrapping_add(ripemd160_f(79 - j, br, cr, dr))
+            .wrapping_add(x[RIPEMD160_R_PRIME[j]])
+            .wrapping_add(RIPEMD160_K_PRIME[j / 16])
+            .rotate_left(RIPEMD160_S_PRIME[j])
+            .wrapping_add(er);
+        ar = er;
+        er = dr;
+        dr = cr.rotate_left(10);
+        cr = br;
+        br = t;
+    }
+    let t = state[1].wrapping_add(cl).wrapping_add(dr);
+    state[1] = state[2].wrapping_add(dl).wrapping_add(er);
+    state[2] = state[3].wrapping_add(el).wrapping_add(ar);
+    state[3] = state[4].wrapping_add(al).wrapping_add(br);
+    state[4] = state[0].wrapping_add(bl).wrapping_add(cr);
+    state[0] = t;
+}
+
+/// RIPEMD-160 digest of `data`.
+pub fn ripemd160(data: &[u8]) -> [u8; 20] {
+    let mut state = RIPEMD160_H;
+    let bit_len = (data.len() as u64) * 8;
+    for block in md_pad(data, 64, &bit_len.to_le_bytes()).chunks(64) {
+        ripemd160_compress(&mut state, block);
+    }
+    let mut out = [0u8; 20];
+    for (chunk, word) in out.chunks_mut(4).zip(state.iter()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    out
+}
+
+/// RIPEMD-160 of SHA-256, the hash committed to by P2PKH and P2SH outputs.
+pub fn hash160(data: &[u8]) -> [u8; 20] {
+    ripemd160(&sha256(data))
+}
+
+/// The tags of BIP340, BIP341 and BIP327, the only ones whose midstates
+/// are cached; a fixed list keeps the cache from growing with caller tags.
+#[cfg(any(feature = "std", test))]
+const KNOWN_TAGS: [&str; 12] = [
+    "BIP0340/aux",
+    "BIP0340/nonce",
+    "BIP0340/challenge",
+    "TapLeaf",
+    "TapBranch",
+    "TapTweak",
+    "TapSighash",
+    "KeyAgg list",
+    "KeyAgg coefficient",
+    "MuSig/aux",
+    "MuSig/nonce",
+    "MuSig/noncecoef",
+];
+
+#[cfg(feature = "std")]
+static KNOWN_MIDSTATES: OnceLock<[[u32; 8]; 12]> = OnceLock::new();
+
+/// SHA-256 state after compressing the block `sha256(tag) || sha256(tag)`.
+fn tag_midstate(tag: &str) -> [u32; 8] {
+    let tag_hash = sha256(tag.as_bytes());
+    let mut block = [0u8; 64];
+    block[..32].copy_from_slice(&tag_hash);
+    block[32..].copy_from_slice(&tag_hash);
+    let mut state = SHA256_H;
+    sha256_compress(&mut state, &block);
+    state
+}
+
+/// The midstate for `tag`: from a table built once for `KNOWN_TAGS` with
+/// `std`, computed afresh for any other tag or without `std`.
+fn cached_midstate(tag: &str) -> [u32; 8] {
+    #[cfg(feature = "std")]
+    if let Some(i) = KNOWN_TAGS.iter().position(|known| *known == tag) {
+        let table = KNOWN_MIDSTATES.get_or_init(|| KNOWN_TAGS.map(tag_midstate));
+        return table[i];
+    }
+    tag_midstate(tag)
+}
+
+/// BIP340 tagged hash `sha256(sha256(tag) || sha256(tag) || msg)`. The two
+/// tag hashes fill exactly one block, so for the tags of `KNOWN_TAGS` the
+/// state after compressing it is precomputed and only `msg` is hashed.
+pub fn tagged_hash(tag: &str, msg: &[u8]) -> [u8; 32] {
+    sha256_resume(cached_midstate(tag), 64, msg)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hex;
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            hex::encode(&sha256(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex::encode(&sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // two-block message
+        assert_eq!(
+            hex::encode(&sha256(
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+            )),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn test_hash256() {
+        assert_eq!(
+            hex::encode(&hash256(b"")),
+            "5df6e0e2761359d30a8275058e299fcc0381534545f55cf43e41983f5d4c9456"
+        );
+    }
+
+    #[test]
+    fn test_ripemd160() {
+        assert_eq!(
+            hex::encode(&ripemd160(b"")),
+            "9c1185a5c5e9fc54612808977ee8f548b2258d31"
+        );
+        assert_eq!(
+            hex::encode(&ripemd160(b"abc")),
+            "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc"
+        );
+        assert_eq!(
+            hex::encode(&ripemd160(b"message digest")),
+            "5d0689ef49d2fae572b881b123a85ffa21595f36"
+        );
+        // two-block message
+        assert_eq!(
+            hex::encode(&ripemd160(
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890"
+            )),
+            "9b752e45573d4b39f4dbd3323cab82bf63326bfb"
+        );
+    }
+
+    #[test]
+    fn test_hash160() {
+        // compressed SEC key for secret 0x12345deadbeef
+        let sec = [
+            0x02, 0x50, 0x86, 0x3a, 0xd6, 0x4a, 0x87, 0xae, 0x8a, 0x2f, 0xe8, 0x3c, 0x1a, 0xf1,
+            0xa8, 0x40, 0x3c, 0xb5, 0x3f, 0x53, 0xe4, 0x86, 0xd8, 0x51, 0x1d, 0xad, 0x8a, 0x04,
+            0x88, 0x7e, 0x5b, 0x23, 0x52,
+        ];
+        assert_eq!(
+            hex::encode(&hash160(&sec)),
+            "f54a5851e9372b87810a8e60cdd2e7cfd80b6e31"
+        );
+    }
+
+    #[test]
+    fn test_tagged_hash() {
+        let cases: [(&str, &[u8], &str); 2] = [
+            (
+                "BIP0340/challenge",
+                b"",
+                "c216d352f5818b7b4beacd4ae0a26fe888080823d2a598856661bcd54f1b3713",
+            ),
+            (
+                "TapLeaf",
+                b"abc",
+                "83a56308a9c56f467e8df293da5ae5fdbc85b871952a83c4bf0575ee948ec230",
+            ),
+        ];
+        for (tag, msg, expected) in cases.iter() {
+            assert_eq!(hex::encode(&tagged_hash(tag, msg)), *expected);
+            // the second call goes through the cached midstate
+            assert_eq!(hex::encode(&tagged_hash(tag, msg)), *expected);
+        }
+        // a tag outside KNOWN_TAGS is hashed directly
+        let tag_hash = sha256(b"not a BIP tag");
+        let mut data = [tag_hash, tag_hash].concat();
+        data.extend_from_slice(b"abc");
+        assert_eq!(tagged_hash("not a BIP tag", b"abc"), sha256(&data));
+        for tag in KNOWN_TAGS.iter() {
+            assert_eq!(cached_midstate(tag), tag_midstate(tag));
+        }
+        let msg: Vec<u8> = (0..100).collect();
+        let tag_hash = sha256(b"BIP0340/nonce");
+        let mut data = [tag_hash, tag_hash].concat();
+        data.extend_from_slice(&msg);
+        assert_eq!(tagged_hash("BIP0340/nonce", &msg), sha256(&data));
+        assert_eq!(
+            hex::encode(&tagged_hash("BIP0340/nonce", &msg)),
+            "75af5c2d095f84bf6c646cbd4822fe468970e236c034785e33c2a1a59c8c13c4"
+        );
+    }
+
+    #[test]
+    fn test_sha512() {
+        assert_eq!(
+            hex::encode(&sha512(b"")),
+            "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"
+        );
+        assert_eq!(
+            hex::encode(&sha512(b"abc")),
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+        );
+        // two-block message
+        assert_eq!(
+            hex::encode(&sha512(b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu")),
+            "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909"
+        );
+    }
+}
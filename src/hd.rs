@@ -0,0 +1,517 @@
+use crate::base58;
+use crate::crypto::hmac_sha512;
+use crate::ecc::to_bytes32;
+use crate::errors::ValueError;
+use crate::generator::mul_generator;
+use crate::hash::hash160;
+use crate::point::{Point, PointOps};
+use crate::secp256k1;
+use ibig::{ubig, UBig};
+use std::fmt;
+use std::str::FromStr;
+
+/// Child indices at or above this are hardened.
+pub const HARDENED: u32 = 0x8000_0000;
+
+const XPRV: [u8; 4] = [0x04, 0x88, 0xad, 0xe4];
+const XPUB: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+const TPRV: [u8; 4] = [0x04, 0x35, 0x83, 0x94];
+const TPUB: [u8; 4] = [0x04, 0x35, 0x87, 0xcf];
+
+/// A BIP32 path such as `m/44'/0'/0'/0/0`, stored as child indices with
+/// hardened ones offset by `HARDENED`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DerivationPath {
+    pub indices: Vec<u32>,
+}
+
+impl FromStr for DerivationPath {
+    type Err = ValueError;
+    fn from_str(s: &str) -> Result<DerivationPath, ValueError> {
+        let invalid = |reason: String| ValueError {
+            message: format!("invalid derivation path {:?}: {}", s, reason),
+        };
+        let mut segments = s.split('/');
+        if segments.next() != Some("m") {
+            return Err(invalid("must start with m".to_string()));
+        }
+        let mut indices = Vec::new();
+        for segment in segments {
+            // a trailing ' or h marks a hardened index
+            let (digits, hardened) = match segment.strip_suffix('\'') {
+                Some(digits) => (digits, true),
+                None => match segment.strip_suffix('h') {
+                    Some(digits) => (digits, true),
+                    None => (segment, false),
+                },
+            };
+            if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+                return Err(invalid(format!("malformed segment {:?}", segment)));
+            }
+            let index = match digits.parse::<u32>() {
+                Ok(index) if index < HARDENED => index,
+                _ => return Err(invalid(format!("index {} out of range", digits))),
+            };
+            indices.push(if hardened { index + HARDENED } else { index });
+        }
+        Ok(DerivationPath { indices })
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "m")?;
+        for index in self.indices.iter() {
+            match *index >= HARDENED {
+                true => write!(f, "/{}'", index - HARDENED)?,
+                false => write!(f, "/{}", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A BIP32 extended key: a key plus the chain code and position metadata
+/// needed to derive children and serialize it as an xprv/xpub.
+#[derive(Clone)]
+pub struct ExtendedKey {
+    pub depth: u8,
+    pub parent_fingerprint: [u8; 4],
+    pub child_number: u32,
+    pub chain_code: [u8; 32],
+    /// The private scalar, or `None` for a public-only extended key.
+    pub secret: Option<UBig>,
+    pub point: Point,
+}
+
+/// Like `PrivateKey`'s, this redacts the secret.
+impl fmt::Debug for ExtendedKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("ExtendedKey")
+            .field("depth", &self.depth)
+            .field("parent_fingerprint", &self.parent_fingerprint)
+            .field("child_number", &self.child_number)
+            .field("chain_code", &self.chain_code)
+            .field(
+                "secret",
+                &self.secret.as_ref().map(|_| format_args!("<redacted>")),
+            )
+            .field("point", &self.point)
+            .finish()
+    }
+}
+
+/// Splits an HMAC-SHA512 output into the tweak `I_L` and the chain code
+/// `I_R`, rejecting the (astronomically unlikely) out-of-range tweak.
+fn split_hmac(i: &[u8; 64]) -> Result<(UBig, [u8; 32]), ValueError> {
+    let tweak = UBig::from_be_bytes(&i[..32]);
+    if tweak >= secp256k1::n() {
+        return Err(ValueError {
+            message: "derived key is invalid, use the next index".to_string(),
+        });
+    }
+    let mut chain_code = [0u8; 32];
+    chain_code.copy_from_slice(&i[32..]);
+    Ok((tweak, chain_code))
+}
+
+impl ExtendedKey {
+    /// The master key for a 16 to 64 byte seed.
+    pub fn from_seed(seed: &[u8]) -> Result<ExtendedKey, ValueError> {
+        if seed.len() < 16 || seed.len() > 64 {
+            return Err(ValueError {
+                message: format!("seed must be 16 to 64 bytes, got {}", seed.len()),
+            });
+        }
+        let (secret, chain_code) = split_hmac(&hmac_sha512(b"Bitcoin seed", seed))?;
+        if secret == ubig!(0) {
+            return Err(ValueError {
+                message: "seed produces an invalid master key".to_string(),
+            });
+        }
+        Ok(ExtendedKey {
+            depth: 0,
+            parent_fingerprint: [0; 4],
+            child_number: 0,
+            chain_code,
+            point: mul_generator(&secret),
+            secret: Some(secret),
+        })
+    }
+
+    /// Parses a Base58Check `xpub` or `tpub` into a public-only key, which
+    /// can derive normal children but not hardened ones.
+    pub fn from_xpub(s: &str) -> Result<ExtendedKey, ValueError> {
+        let invalid = |reason: &str| ValueError {
+            message: format!("invalid xpub: {}", reason),
+        };
+        let payload = base58::decode_check(s)?;
+        if payload.len() != 78 {
+            return Err(invalid(&format!(
+                "expected 78 bytes, got {}",
+                payload.len()
+            )));
+        }
+        if payload[..4] != XPUB && payload[..4] != TPUB {
+            return Err(invalid("unknown version"));
+        }
+        let depth = payload[4];
+        let mut parent_fingerprint = [0u8; 4];
+        parent_fingerprint.copy_from_slice(&payload[5..9]);
+        let mut child_number = [0u8; 4];
+        child_number.copy_from_slice(&payload[9..13]);
+        let child_number = u32::from_be_bytes(child_number);
+        if depth == 0 && (parent_fingerprint != [0; 4] || child_number != 0) {
+            return Err(invalid("master key with a parent"));
+        }
+        let mut chain_code = [0u8; 32];
+        chain_code.copy_from_slice(&payload[13..45]);
+        // Point::parse would also take a 65-byte key; an xpub must be compressed
+        if payload[45] != 0x02 && payload[45] != 0x03 {
+            return Err(invalid("key is not a compressed public key"));
+        }
+        let point = Point::parse(&payload[45..])?;
+        Ok(ExtendedKey {
+            depth,
+            parent_fingerprint,
+            child_number,
+            chain_code,
+            secret: None,
+            point,
+        })
+    }
+
+    /// First four bytes of the hash160 of the compressed public key.
+    pub fn fingerprint(&self) -> [u8; 4] {
+        let mut out = [0u8; 4];
+        out.copy_from_slice(&hash160(&self.public_sec())[..4]);
+        out
+    }
+
+    /// The public-only counterpart of this key.
+    pub fn neuter(&self) -> ExtendedKey {
+        ExtendedKey {
+            secret: None,
+            ..self.clone()
+        }
+    }
+
+    /// Derives child `index`; indices from `HARDENED` up are hardened and
+    /// need the private key.
+    pub fn derive_child(&self, index: u32) -> Result<ExtendedKey, ValueError> {
+        let mut data = Vec::with_capacity(37);
+        if index >= HARDENED {
+            let secret = self.secret.as_ref().ok_or_else(|| ValueError {
+                message: "cannot derive a hardened child from a public key".to_string(),
+            })?;
+            data.push(0);
+            data.extend_from_slice(&to_bytes32(secret));
+        } else {
+            data.extend(self.public_sec());
+        }
+        data.extend_from_slice(&index.to_be_bytes());
+        let (tweak, chain_code) = split_hmac(&hmac_sha512(&self.chain_code, &data))?;
+
+        let invalid = || ValueError {
+            message: "derived key is invalid, use the next index".to_string(),
+        };
+        let (secret, point) = match &self.secret {
+            Some(secret) => {
+                let child = (tweak + secret) % secp256k1::n();
+                if child == ubig!(0) {
+                    return Err(invalid());
+                }
+                let point = mul_generator(&child);
+                (Some(child), point)
+            }
+            None => {
+                let point = &mul_generator(&tweak) + &self.point;
+                if point.is_infinity() {
+                    return Err(invalid());
+                }
+                (None, point)
+            }
+        };
+        Ok(ExtendedKey {
+            depth: self.depth.checked_add(1).ok_or_else(|| ValueError {
+                message: "maximum derivation depth reached".to_string(),
+            })?,
+            parent_fingerprint: self.fingerprint(),
+            child_number: index,
+            chain_code,
+            secret,
+            point,
+        })
+    }
+
+    /// Derives each index of `path` in turn from this key.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<ExtendedKey, ValueError> {
+        let mut key = self.clone();
+        for index in path.indices.iter() {
+            key = key.derive_child(*index)?;
+        }
+        Ok(key)
+    }
+
+    fn serialize(&self, version: [u8; 4], key: &[u8]) -> String {
+        let mut payload = Vec::with_capacity(78);
+        payload.extend_from_slice(&version);
+        payload.push(self.depth);
+        payload.extend_from_slice(&self.parent_fingerprint);
+        payload.extend_from_slice(&self.child_number.to_be_bytes());
+        payload.extend_from_slice(&self.chain_code);
+        payload.extend_from_slice(key);
+        base58::encode_check(&payload)
+    }
+
+    /// Base58Check `xprv` (or `tprv`) serialization; errors for a public key.
+    pub fn xprv(&self, testnet: bool) -> Result<String, ValueError> {
+        let secret = self.secret.as_ref().ok_or_else(|| ValueError {
+            message: "public extended key has no xprv".to_string(),
+        })?;
+        let mut key = vec![0];
+        key.extend_from_slice(&to_bytes32(secret));
+        Ok(self.serialize(if testnet { TPRV } else { XPRV }, &key))
+    }
+
+    /// Base58Check `xpub` (or `tpub`) serialization.
+    pub fn xpub(&self, testnet: bool) -> String {
+        self.serialize(if testnet { TPUB } else { XPUB }, &self.public_sec())
+    }
+
+    /// The compressed public key. Derivation rejects a child at infinity,
+    /// so every `ExtendedKey` has one.
+    fn public_sec(&self) -> Vec<u8> {
+        self.point
+            .sec(true)
+            .expect("an extended key is never the point at infinity")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hex;
+
+    // BIP32 test vector 1: (child index, xpub, xprv) for m, m/0H, m/0H/1,
+    // m/0H/1/2H, m/0H/1/2H/2 and m/0H/1/2H/2/1000000000
+    const VECTOR_1: [(u32, &str, &str); 6] = [
+        (
+            0,
+            "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8",
+            "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi",
+        ),
+        (
+            HARDENED,
+            "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw",
+            "xprv9uHRZZhk6KAJC1avXpDAp4MDc3sQKNxDiPvvkX8Br5ngLNv1TxvUxt4cV1rGL5hj6KCesnDYUhd7oWgT11eZG7XnxHrnYeSvkzY7d2bhkJ7",
+        ),
+        (
+            1,
+            "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ",
+            "xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs",
+        ),
+        (
+            HARDENED + 2,
+            "xpub6D4BDPcP2GT577Vvch3R8wDkScZWzQzMMUm3PWbmWvVJrZwQY4VUNgqFJPMM3No2dFDFGTsxxpG5uJh7n7epu4trkrX7x7DogT5Uv6fcLW5",
+            "xprv9z4pot5VBttmtdRTWfWQmoH1taj2axGVzFqSb8C9xaxKymcFzXBDptWmT7FwuEzG3ryjH4ktypQSAewRiNMjANTtpgP4mLTj34bhnZX7UiM",
+        ),
+        (
+            2,
+            "xpub6FHa3pjLCk84BayeJxFW2SP4XRrFd1JYnxeLeU8EqN3vDfZmbqBqaGJAyiLjTAwm6ZLRQUMv1ZACTj37sR62cfN7fe5JnJ7dh8zL4fiyLHV",
+            "xprvA2JDeKCSNNZky6uBCviVfJSKyQ1mDYahRjijr5idH2WwLsEd4Hsb2Tyh8RfQMuPh7f7RtyzTtdrbdqqsunu5Mm3wDvUAKRHSC34sJ7in334",
+        ),
+        (
+            1000000000,
+            "xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy",
+            "xprvA41z7zogVVwxVSgdKUHDy1SKmdb533PjDz7J6N6mV6uS3ze1ai8FHa8kmHScGpWmj4WggLyQjgPie1rFSruoUihUZREPSL39UNdE3BBDu76",
+        ),
+    ];
+
+    #[test]
+    fn test_vector_1() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let mut key = ExtendedKey::from_seed(&seed).unwrap();
+        for (i, (index, xpub, xprv)) in VECTOR_1.iter().enumerate() {
+            if i > 0 {
+                key = key.derive_child(*index).unwrap();
+            }
+            assert_eq!(key.depth as usize, i);
+            assert_eq!(key.xpub(false), *xpub);
+            assert_eq!(key.xprv(false).unwrap(), *xprv);
+        }
+    }
+
+    #[test]
+    fn test_public_derivation() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let parent = ExtendedKey::from_seed(&seed)
+            .unwrap()
+            .derive_child(HARDENED)
+            .unwrap()
+            .derive_child(1)
+            .unwrap()
+            .derive_child(HARDENED + 2)
+            .unwrap();
+        // normal children can be derived from the xpub alone
+        let child = parent.neuter().derive_child(2).unwrap();
+        assert_eq!(child.xpub(false), VECTOR_1[4].1);
+        assert!(child.secret.is_none());
+        assert!(child.xprv(false).is_err());
+        assert!(parent.neuter().derive_child(HARDENED).is_err());
+    }
+
+    #[test]
+    fn test_from_xpub() {
+        for (_, xpub, _) in VECTOR_1.iter() {
+            let key = ExtendedKey::from_xpub(xpub).unwrap();
+            assert!(key.secret.is_none());
+            assert_eq!(key.xpub(false), *xpub);
+        }
+        let m_0h = ExtendedKey::from_xpub(VECTOR_1[1].1).unwrap();
+        assert_eq!(m_0h.derive_child(1).unwrap().xpub(false), VECTOR_1[2].1);
+        assert!(m_0h.derive_child(HARDENED).is_err());
+
+        // the m/44'/0'/0' account of the all-"abandon" BIP39 mnemonic
+        let account = ExtendedKey::from_xpub(
+            "xpub6BosfCnifzxcFwrSzQiqu2DBVTshkCXacvNsWGYJVVhhawA7d4R5WSWGFNbi8Aw6ZRc1brxMyWMzG3DSSSSoekkudhUd9yLb6qx39T9nMdj",
+        )
+        .unwrap();
+        let receive = account.derive_path(&"m/0/0".parse().unwrap()).unwrap();
+        assert_eq!(
+            receive.point.address(true, false).unwrap(),
+            "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA"
+        );
+
+        let tpub = m_0h.xpub(true);
+        assert_eq!(ExtendedKey::from_xpub(&tpub).unwrap().xpub(true), tpub);
+    }
+
+    #[test]
+    fn test_from_xpub_invalid() {
+        let payload = base58::decode_check(VECTOR_1[0].1).unwrap();
+        let reencode = |edit: &dyn Fn(&mut Vec<u8>)| {
+            let mut bad = payload.clone();
+            edit(&mut bad);
+            ExtendedKey::from_xpub(&base58::encode_check(&bad))
+                .unwrap_err()
+                .message
+        };
+        assert_eq!(
+            reencode(&|p| p.truncate(77)),
+            "invalid xpub: expected 78 bytes, got 77"
+        );
+        assert_eq!(
+            reencode(&|p| p[..4].copy_from_slice(&XPRV)),
+            "invalid xpub: unknown version"
+        );
+        assert_eq!(
+            reencode(&|p| p[5] = 1),
+            "invalid xpub: master key with a parent"
+        );
+        assert_eq!(
+            reencode(&|p| p[12] = 1),
+            "invalid xpub: master key with a parent"
+        );
+        assert_eq!(
+            reencode(&|p| p[45] = 0x04),
+            "invalid xpub: key is not a compressed public key"
+        );
+        // x = 5 has no point on the curve
+        assert_eq!(
+            reencode(&|p| {
+                p[46..].copy_from_slice(&[0; 32]);
+                p[77] = 5;
+            }),
+            "point is not on the curve"
+        );
+        assert!(ExtendedKey::from_xpub(VECTOR_1[0].2).is_err());
+        assert!(ExtendedKey::from_xpub("xpub").is_err());
+    }
+
+    #[test]
+    fn test_debug_redacts_secret() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedKey::from_seed(&seed).unwrap();
+        let secret = master.secret.clone().unwrap();
+        let debug = format!("{:?}", master);
+        assert!(debug.contains("secret: Some(<redacted>)"), "{}", debug);
+        assert!(!debug.contains(&secret.to_string()));
+        assert!(!debug.contains(&format!("{:x}", secret)));
+        assert!(format!("{:?}", master.neuter()).contains("secret: None"));
+    }
+
+    #[test]
+    fn test_testnet_and_seed_length() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedKey::from_seed(&seed).unwrap();
+        assert!(master.xpub(true).starts_with("tpub"));
+        assert!(master.xprv(true).unwrap().starts_with("tprv"));
+        assert!(ExtendedKey::from_seed(&[0; 15]).is_err());
+        assert!(ExtendedKey::from_seed(&[0; 65]).is_err());
+    }
+
+    #[test]
+    fn test_parse_path() {
+        let path: DerivationPath = "m/44'/0'/0'/0/0".parse().unwrap();
+        assert_eq!(path.indices, vec![HARDENED + 44, HARDENED, HARDENED, 0, 0]);
+        assert_eq!(path.to_string(), "m/44'/0'/0'/0/0");
+        assert_eq!(
+            "m/44h/0h".parse::<DerivationPath>().unwrap().indices,
+            vec![HARDENED + 44, HARDENED]
+        );
+        assert_eq!(
+            "m".parse::<DerivationPath>().unwrap(),
+            DerivationPath::default()
+        );
+        assert_eq!(
+            "m/2147483647'".parse::<DerivationPath>().unwrap().indices,
+            vec![u32::MAX]
+        );
+        for bad in [
+            "",
+            "44/0",
+            "m/",
+            "m//1",
+            "m/x",
+            "m/1''",
+            "m/-1",
+            "m/+1",
+            "m/2147483648",
+            "n/1",
+        ]
+        .iter()
+        {
+            assert!(
+                bad.parse::<DerivationPath>().is_err(),
+                "{:?} should not parse",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_derive_path() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedKey::from_seed(&seed).unwrap();
+        let path = "m/0'/1/2'/2/1000000000".parse().unwrap();
+        assert_eq!(
+            master.derive_path(&path).unwrap().xpub(false),
+            VECTOR_1[5].1
+        );
+
+        let account = master
+            .derive_path(&"m/44'/0'/0'/0/0".parse().unwrap())
+            .unwrap();
+        assert_eq!(account.depth, 5);
+        assert_eq!(account.child_number, 0);
+        assert_eq!(
+            account.xpub(false),
+            "xpub6H9VcQiRXzkBR4RS3tU6RSXb8ouGRKQr1f1NXfTinCfTxvEhygCiJ4TDLHz1dyQ6d2Vz8Ne7eezkrViwaPo2ZMsNjVtFwvzsQXCDV6HJ3cV"
+        );
+        assert!(master
+            .neuter()
+            .derive_path(&"m/44'".parse().unwrap())
+            .is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use crate::errors::ValueError;
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Encodes `bytes` as lowercase hex.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        out.push(DIGITS[(byte >> 4) as usize] as char);
+        out.push(DIGITS[(byte & 0x0f) as usize] as char);
+    }
+    out
+}
+
+/// Decodes a hex string, accepting either case. Fails on an odd number of
+/// digits or any character that is not a hex digit.
+pub fn decode(s: &str) -> Result<Vec<u8>, ValueError> {
+    if !s.len().is_multiple_of(2) {
+        return Err(ValueError {
+            message: format!("odd-length hex string of {} characters", s.len()),
+        });
+    }
+    let digits = s
+        .char_indices()
+        .map(|(i, c)| {
+            c.to_digit(16).map(|d| d as u8).ok_or_else(|| ValueError {
+                message: format!("invalid hex character {:?} at position {}", c, i),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(digits
+        .chunks(2)
+        .map(|pair| (pair[0] << 4) | pair[1])
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let encoded = encode(&bytes);
+        assert_eq!(&encoded[..8], "00010203");
+        assert_eq!(&encoded[encoded.len() - 4..], "feff");
+        assert_eq!(decode(&encoded).unwrap(), bytes);
+        assert_eq!(decode("DEADbeef").unwrap(), vec![0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(encode(&[]), "");
+        assert_eq!(decode("").unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_decode_odd_length() {
+        let err = decode("abc").unwrap_err();
+        assert_eq!(err.message, "odd-length hex string of 3 characters");
+    }
+
+    #[test]
+    fn test_decode_invalid_character() {
+        let err = decode("00zz").unwrap_err();
+        assert_eq!(err.message, "invalid hex character 'z' at position 2");
+        // a multi-byte character is rejected whole, not split into bytes
+        let err = decode("éé").unwrap_err();
+        assert_eq!(err.message, "invalid hex character 'é' at position 0");
+    }
+}
@@ -0,0 +1,239 @@
+use crate::ecc::{FieldElement, FieldElementOps};
+use crate::point::{Point, PointOps};
+use core::ops::Add;
+use ibig::UBig;
+
+/// A curve point in Jacobian coordinates, standing for the affine point
+/// `(X/Z^2, Y/Z^3)`. Addition and doubling need no field inversion, so a
+/// whole scalar multiplication pays for a single inversion in `to_affine`.
+/// `Z == 0` represents the point at infinity.
+#[derive(Clone, Debug)]
+pub struct JacobianPoint {
+    pub x: FieldElement,
+    pub y: FieldElement,
+    pub z: FieldElement,
+    pub a: FieldElement,
+    pub b: FieldElement,
+}
+
+impl JacobianPoint {
+    pub fn infinity(a: FieldElement, b: FieldElement) -> JacobianPoint {
+        let one = FieldElement::from_u64(1, a.prime.clone());
+        let zero = FieldElement::from_u64(0, a.prime.clone());
+        JacobianPoint {
+            x: one.clone(),
+            y: one,
+            z: zero,
+            a,
+            b,
+        }
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    /// Exchanges the coordinates of `self` and `other` when `swap` is set.
+    /// Both are rewritten either way: the fixed-width encodings are XORed
+    /// under a mask derived from `swap`, so no branch depends on it. Both
+    /// points must be on the same curve.
+    pub fn conditional_swap(&mut self, other: &mut JacobianPoint, swap: bool) {
+        let mask = 0u8.wrapping_sub(swap as u8);
+        let len = self.x.prime.bit_len().div_ceil(8);
+        let pairs = [
+            (&mut self.x, &mut other.x),
+            (&mut self.y, &mut other.y),
+            (&mut self.z, &mut other.z),
+        ];
+        for (a, b) in pairs {
+            let mut a_bytes = a.to_be_bytes(len);
+            let mut b_bytes = b.to_be_bytes(len);
+            for (x, y) in a_bytes.iter_mut().zip(b_bytes.iter_mut()) {
+                let t = (*x ^ *y) & mask;
+                *x ^= t;
+                *y ^= t;
+            }
+            a.num = UBig::from_be_bytes(&a_bytes);
+            b.num = UBig::from_be_bytes(&b_bytes);
+        }
+    }
+
+    pub fn to_affine(&self) -> Point {
+        if self.is_infinity() {
+            return Point::infinity(self.a.clone(), self.b.clone());
+        }
+        let z_inv = self.z.inverse().unwrap();
+        let z_inv2 = &z_inv * &z_inv;
+        let z_inv3 = &z_inv2 * &z_inv;
+        Point {
+            x: Some(&self.x * &z_inv2),
+            y: Some(&self.y * &z_inv3),
+            a: self.a.clone(),
+            b: self.b.clone(),
+        }
+    }
+
+    pub fn double(&self) -> JacobianPoint {
+        if self.is_infinity() || self.y.is_zero() {
+            return JacobianPoint::infinity(self.a.clone(), self.b.clone());
+        }
+        let xx = &self.x * &self.x;
+        let yy = &self.y * &self.y;
+        let zz = &self.z * &self.z;
+        let s = (&self.x * &yy).mul_small(4);
+        // M = 3X^2 + aZ^4 is the tangent slope scaled by 2YZ
+        let m = &xx.mul_small(3) + &(&self.a * &(&zz * &zz));
+        let x3 = &(&m * &m) - &s.double();
+        let y3 = &(&m * &(&s - &x3)) - &(&yy * &yy).mul_small(8);
+        let z3 = (&self.y * &self.z).double();
+        JacobianPoint {
+            x: x3,
+            y: y3,
+            z: z3,
+            a: self.a.clone(),
+            b: self.b.clone(),
+        }
+    }
+
+    pub fn scalar_mul(&self, coeff: &UBig) -> JacobianPoint {
+        // double-and-add from the most significant bit down
+        let mut result = JacobianPoint::infinity(self.a.clone(), self.b.clone());
+        for i in (0..coeff.bit_len()).rev() {
+            result = result.double();
+            if coeff.bit(i) {
+                result = &result + self;
+            }
+        }
+        result
+    }
+}
+
+impl<'a> Add<&'a JacobianPoint> for &'a JacobianPoint {
+    type Output = JacobianPoint;
+    fn add(self, rhs: &JacobianPoint) -> JacobianPoint {
+        assert!(
+            self.a == rhs.a && self.b == rhs.b,
+            "points are not on the same curve"
+        );
+        if self.is_infinity() {
+            return rhs.clone();
+        }
+        if rhs.is_infinity() {
+            return self.clone();
+        }
+        // bring both points to the common denominator Z1^2 * Z2^2
+        let z1z1 = &self.z * &self.z;
+        let z2z2 = &rhs.z * &rhs.z;
+        let u1 = &self.x * &z2z2;
+        let u2 = &rhs.x * &z1z1;
+        let s1 = &self.y * &(&rhs.z * &z2z2);
+        let s2 = &rhs.y * &(&self.z * &z1z1);
+        if u1 == u2 {
+            return match s1 == s2 {
+                true => self.double(),
+                false => JacobianPoint::infinity(self.a.clone(), self.b.clone()),
+            };
+        }
+        let h = &u2 - &u1;
+        let r = &s2 - &s1;
+        let hh = &h * &h;
+        let hhh = &hh * &h;
+        let v = &u1 * &hh;
+        let x3 = &(&(&r * &r) - &hhh) - &(&v + &v);
+        let y3 = &(&r * &(&v - &x3)) - &(&s1 * &hhh);
+        let z3 = &h * &(&self.z * &rhs.z);
+        JacobianPoint {
+            x: x3,
+            y: y3,
+            z: z3,
+            a: self.a.clone(),
+            b: self.b.clone(),
+        }
+    }
+}
+
+impl Add for JacobianPoint {
+    type Output = JacobianPoint;
+    fn add(self, rhs: Self) -> Self {
+        &self + &rhs
+    }
+}
+
+impl PartialEq for JacobianPoint {
+    fn eq(&self, other: &JacobianPoint) -> bool {
+        // equal when they describe the same affine point
+        self.to_affine() == other.to_affine()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::secp256k1;
+    use ibig::ubig;
+
+    fn fe(num: u32) -> FieldElement {
+        FieldElement::from_u64(u64::from(num), ubig!(223))
+    }
+
+    fn point(x: u32, y: u32) -> Point {
+        Point::new(Some(fe(x)), Some(fe(y)), fe(0), fe(7)).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let p = point(47, 71);
+        assert_eq!(p.to_jacobian().to_affine(), p);
+        let inf = Point::infinity(fe(0), fe(7));
+        assert!(inf.to_jacobian().is_infinity());
+        assert_eq!(inf.to_jacobian().to_affine(), inf);
+    }
+
+    #[test]
+    fn test_conditional_swap() {
+        let (p, q) = (point(47, 71), point(17, 56));
+        let inf = Point::infinity(fe(0), fe(7));
+        for (a, b) in [(&p, &q), (&p, &inf)] {
+            let (mut x, mut y) = (a.to_jacobian(), b.to_jacobian());
+            x.conditional_swap(&mut y, false);
+            assert_eq!((x.to_affine(), y.to_affine()), (a.clone(), b.clone()));
+            x.conditional_swap(&mut y, true);
+            assert_eq!((x.to_affine(), y.to_affine()), (b.clone(), a.clone()));
+        }
+    }
+
+    #[test]
+    fn test_add() {
+        let cases = [
+            ((192, 105), (17, 56)),
+            ((47, 71), (117, 141)),
+            ((143, 98), (76, 66)),
+            ((47, 71), (47, 71)),
+            ((47, 71), (47, 152)),
+        ];
+        for ((x1, y1), (x2, y2)) in cases.iter() {
+            let (p1, p2) = (point(*x1, *y1), point(*x2, *y2));
+            let sum = &p1.to_jacobian() + &p2.to_jacobian();
+            assert_eq!(sum.to_affine(), &p1 + &p2);
+        }
+    }
+
+    #[test]
+    fn test_scalar_mul_223() {
+        let p = point(15, 86);
+        for k in 0..=8u32 {
+            let k = UBig::from(k);
+            assert_eq!(p.to_jacobian().scalar_mul(&k).to_affine(), p.scalar_mul(&k));
+        }
+        // (15, 86) has order 7
+        assert!(p.to_jacobian().scalar_mul(&ubig!(7)).is_infinity());
+    }
+
+    #[test]
+    fn test_scalar_mul_secp256k1() {
+        let g = secp256k1::g();
+        let k = UBig::from_str_radix("deadbeef12345678deadbeef12345678", 16).unwrap();
+        assert_eq!(g.to_jacobian().scalar_mul(&k).to_affine(), g.scalar_mul(&k));
+        assert!(g.to_jacobian().scalar_mul(&secp256k1::n()).is_infinity());
+    }
+}
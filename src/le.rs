@@ -0,0 +1,73 @@
+//! Little-endian readers and writers for the fixed-width integers used in
+//! Bitcoin serialization. `u32`/`u64` encode with the standard
+//! `to_le_bytes`; the readers here take the leading bytes of a slice and
+//! error instead of panicking when it is too short.
+
+use crate::errors::ValueError;
+use ibig::UBig;
+
+fn leading(bytes: &[u8], len: usize) -> Result<&[u8], ValueError> {
+    bytes.get(..len).ok_or_else(|| ValueError {
+        message: format!("need {} bytes, got {}", len, bytes.len()),
+    })
+}
+
+pub fn u32_from_le_bytes(bytes: &[u8]) -> Result<u32, ValueError> {
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(leading(bytes, 4)?);
+    Ok(u32::from_le_bytes(buf))
+}
+
+pub fn u64_from_le_bytes(bytes: &[u8]) -> Result<u64, ValueError> {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(leading(bytes, 8)?);
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Reads a 32-byte little-endian field element or scalar.
+pub fn ubig_from_le_bytes(bytes: &[u8]) -> Result<UBig, ValueError> {
+    Ok(UBig::from_le_bytes(leading(bytes, 32)?))
+}
+
+/// Little-endian encoding of `num`, right-padded to 32 bytes.
+pub fn ubig_to_le_bytes(num: &UBig) -> [u8; 32] {
+    let bytes = num.to_le_bytes();
+    let mut out = [0u8; 32];
+    out[..bytes.len()].copy_from_slice(&bytes);
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ibig::ubig;
+
+    #[test]
+    fn test_u32() {
+        for n in [0, 1, 0x12345678, u32::MAX].iter() {
+            assert_eq!(u32_from_le_bytes(&n.to_le_bytes()).unwrap(), *n);
+        }
+        assert_eq!(u32_from_le_bytes(&[1, 0, 0, 0, 0xff]).unwrap(), 1);
+        assert!(u32_from_le_bytes(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_u64() {
+        for n in [0, 1, 0x0123456789abcdef, u64::MAX].iter() {
+            assert_eq!(u64_from_le_bytes(&n.to_le_bytes()).unwrap(), *n);
+        }
+        assert!(u64_from_le_bytes(&[0; 7]).is_err());
+    }
+
+    #[test]
+    fn test_ubig() {
+        let max = (ubig!(1) << 256) - ubig!(1);
+        for n in [ubig!(0), ubig!(1), ubig!(0xdeadbeef), max].iter() {
+            assert_eq!(ubig_from_le_bytes(&ubig_to_le_bytes(n)).unwrap(), *n);
+        }
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        assert_eq!(ubig_to_le_bytes(&ubig!(1)), one);
+        assert!(ubig_from_le_bytes(&[0; 31]).is_err());
+    }
+}
@@ -0,0 +1,54 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod address;
+#[cfg(feature = "std")]
+pub mod amount;
+pub mod barrett;
+pub mod base58;
+#[cfg(feature = "std")]
+pub mod base64;
+#[cfg(feature = "std")]
+pub mod bech32;
+pub mod crypto;
+pub mod curve;
+#[cfg(feature = "std")]
+pub mod descriptor;
+pub mod ecc;
+#[cfg(feature = "std")]
+pub mod ecdsa;
+pub mod errors;
+pub mod fp256;
+#[cfg(feature = "std")]
+pub mod generator;
+pub mod hash;
+#[cfg(feature = "std")]
+pub mod hd;
+pub mod hex;
+pub mod jacobian;
+#[cfg(feature = "std")]
+pub mod le;
+#[cfg(feature = "std")]
+pub mod message;
+#[cfg(feature = "std")]
+pub mod mnemonic;
+#[cfg(feature = "std")]
+pub mod musig;
+pub mod point;
+pub mod scalar;
+#[cfg(feature = "std")]
+pub mod schnorr;
+#[cfg(feature = "std")]
+pub mod script;
+pub mod secp256k1;
+#[cfg(feature = "std")]
+pub mod taproot;
+#[cfg(feature = "std")]
+pub mod tx;
+#[cfg(feature = "std")]
+pub mod varint;
+#[cfg(feature = "wasm")]
+pub mod wasm;
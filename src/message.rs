@@ -0,0 +1,174 @@
+use crate::address::{decode_address, AddressType};
+use crate::base64;
+use crate::ecdsa::{recover_public_key, PrivateKey, Signature};
+use crate::hash::{hash160, hash256};
+use crate::point::PointOps;
+use crate::script::p2wpkh_script;
+use crate::varint::encode_varint;
+use ibig::UBig;
+
+const MAGIC: &[u8] = b"\x18Bitcoin Signed Message:\n";
+
+/// The digest a signed message commits to:
+/// `hash256("\x18Bitcoin Signed Message:\n" || varint(len) || message)`.
+pub fn message_hash(message: &str) -> [u8; 32] {
+    let mut preimage = MAGIC.to_vec();
+    preimage.extend(encode_varint(message.len() as u64));
+    preimage.extend_from_slice(message.as_bytes());
+    hash256(&preimage)
+}
+
+/// Signs `message` the way Bitcoin Core's `signmessage` does: a compact
+/// signature for the key's compressed P2PKH address, Base64 encoded.
+pub fn sign_message(privkey: &PrivateKey, message: &str) -> String {
+    let z = UBig::from_be_bytes(&message_hash(message));
+    let sig = privkey.sign(&z);
+    let recovery_id = (0..4)
+        .find(|id| recover_public_key(&sig, &z, *id).ok().as_ref() == Some(privkey.public_key()))
+        .expect("no recovery id gives back the signing key");
+    base64::encode(&sig.to_compact(recovery_id, true))
+}
+
+/// Checks a Base64 compact signature over `message` against a mainnet or
+/// testnet address. Besides the P2PKH headers 27 to 34, the BIP137 headers
+/// 35 to 38 (P2SH-P2WPKH) and 39 to 42 (P2WPKH) are accepted for the
+/// matching address types.
+pub fn verify_message(address: &str, signature: &str, message: &str) -> bool {
+    let (address_type, payload) =
+        match decode_address(address, false).or_else(|_| decode_address(address, true)) {
+            Ok(decoded) => decoded,
+            Err(_) => return false,
+        };
+    let mut compact = match base64::decode(signature) {
+        Ok(bytes) if bytes.len() == 65 => bytes,
+        _ => return false,
+    };
+    let expected_type = match compact[0] {
+        27..=34 => AddressType::P2pkh,
+        35..=38 => AddressType::P2sh,
+        39..=42 => AddressType::P2wpkh,
+        _ => return false,
+    };
+    if address_type != expected_type {
+        return false;
+    }
+    // segwit headers imply a compressed key; map them onto 31 to 34
+    if compact[0] > 34 {
+        compact[0] = 31 + (compact[0] - 35) % 4;
+    }
+    let (sig, recovery_id, compressed) = match Signature::parse_compact(&compact) {
+        Ok(parsed) => parsed,
+        Err(_) => return false,
+    };
+    let z = UBig::from_be_bytes(&message_hash(message));
+    let point = match recover_public_key(&sig, &z, recovery_id) {
+        Ok(point) => point,
+        Err(_) => return false,
+    };
+    let key_hash = match point.sec(compressed) {
+        Ok(sec) => hash160(&sec),
+        Err(_) => return false,
+    };
+    let expected = match address_type {
+        AddressType::P2sh => hash160(&p2wpkh_script(&key_hash).raw_serialize()),
+        _ => key_hash,
+    };
+    payload == expected
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hex;
+
+    const MESSAGE: &str = "vires is numeris";
+
+    fn key() -> PrivateKey {
+        PrivateKey::from_wif("5KYZdUEo39z3FPrtuX2QbbwGnNP5zTd7yyr2SC1j299sBCnWjss")
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn test_message_hash() {
+        assert_eq!(
+            hex::encode(&message_hash("")),
+            "80e795d4a4caadd7047af389d9f7f220562feb6196032e2131e10563352c4bcc"
+        );
+    }
+
+    #[test]
+    fn test_sign_message() {
+        // Bitcoin Core signs with the same RFC 6979 nonce, so its
+        // signmessage output for this key is reproduced byte for byte
+        let sig = sign_message(&key(), MESSAGE);
+        assert_eq!(
+            sig,
+            "HwxMsQJ+s7mMjDBiCFyRfHk5SOdPnfIQbszlBLNIBE6efexHARz5is8619IZ1XLlfXDlTzlYxaY/XaLRv/oKlxQ="
+        );
+        assert!(verify_message(
+            "1F3sAm6ZtwLAUnj7d38pGFxtP3RVEvtsbV",
+            &sig,
+            MESSAGE
+        ));
+        assert!(!verify_message(
+            "1F3sAm6ZtwLAUnj7d38pGFxtP3RVEvtsbV",
+            &sig,
+            "vires in numeris"
+        ));
+        // the same key's uncompressed address needs the uncompressed header
+        assert!(!verify_message(
+            "1HZwkjkeaoZfTSaJxDw6aKkxp45agDiEzN",
+            &sig,
+            MESSAGE
+        ));
+        assert!(verify_message(
+            "1HZwkjkeaoZfTSaJxDw6aKkxp45agDiEzN",
+            "GwxMsQJ+s7mMjDBiCFyRfHk5SOdPnfIQbszlBLNIBE6efexHARz5is8619IZ1XLlfXDlTzlYxaY/XaLRv/oKlxQ=",
+            MESSAGE
+        ));
+    }
+
+    #[test]
+    fn test_verify_segwit_headers() {
+        let key = key();
+        let compact = base64::decode(&sign_message(&key, MESSAGE)).unwrap();
+        let recovery_id = compact[0] - 31;
+        let key_hash = hash160(&key.public_key().sec(true).unwrap());
+        let mut p2wpkh = compact.clone();
+        p2wpkh[0] = 39 + recovery_id;
+        let mut p2sh = compact;
+        p2sh[0] = 35 + recovery_id;
+        let bech32_address = crate::bech32::p2wpkh_address(&key_hash, false);
+        let p2sh_address = p2wpkh_script(&key_hash).p2sh_address(false);
+        assert!(verify_message(
+            &bech32_address,
+            &base64::encode(&p2wpkh),
+            MESSAGE
+        ));
+        assert!(verify_message(
+            &p2sh_address,
+            &base64::encode(&p2sh),
+            MESSAGE
+        ));
+        // a header must match the address type
+        assert!(!verify_message(
+            &bech32_address,
+            &base64::encode(&p2sh),
+            MESSAGE
+        ));
+        assert!(!verify_message(
+            "1F3sAm6ZtwLAUnj7d38pGFxtP3RVEvtsbV",
+            &base64::encode(&p2wpkh),
+            MESSAGE
+        ));
+    }
+
+    #[test]
+    fn test_verify_malformed() {
+        let address = "1F3sAm6ZtwLAUnj7d38pGFxtP3RVEvtsbV";
+        assert!(!verify_message(address, "not base64!", MESSAGE));
+        assert!(!verify_message(address, "Zm9vYmFy", MESSAGE));
+        assert!(!verify_message("1BadAddress", "Zm9vYmFy", MESSAGE));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::crypto::pbkdf2_hmac_sha512;
+use crate::errors::ValueError;
+use crate::hash::sha256;
+
+const ENGLISH: &str = include_str!("wordlists/english.txt");
+
+/// BIP39 mnemonic for 16 to 32 bytes of entropy (a multiple of 4). The
+/// first `len / 4` bits of the entropy's SHA-256 are appended as a checksum
+/// and every 11 bits pick a word from the English list.
+pub fn entropy_to_mnemonic(entropy: &[u8]) -> Result<Vec<String>, ValueError> {
+    if ![16, 20, 24, 28, 32].contains(&entropy.len()) {
+        return Err(ValueError {
+            message: format!(
+                "entropy must be 16 to 32 bytes in steps of 4, got {}",
+                entropy.len()
+            ),
+        });
+    }
+    let words: Vec<&str> = ENGLISH.lines().collect();
+    let checksum = sha256(entropy)[0];
+    let mut bits = Vec::with_capacity(entropy.len() * 8 + 8);
+    for byte in entropy.iter().chain(std::iter::once(&checksum)) {
+        bits.extend((0..8).rev().map(|i| (byte >> i) & 1 == 1));
+    }
+    bits.truncate(entropy.len() * 8 + entropy.len() / 4);
+    Ok(bits
+        .chunks(11)
+        .map(|chunk| {
+            let index = chunk.iter().fold(0, |acc, bit| (acc << 1) | *bit as usize);
+            words[index].to_string()
+        })
+        .collect())
+}
+
+/// The 64-byte BIP32 seed for a mnemonic: PBKDF2-HMAC-SHA512 over the
+/// space-joined words, salted with `"mnemonic" + passphrase`, 2048 rounds.
+/// Inputs are used as given; callers with non-ASCII passphrases must apply
+/// NFKD normalization themselves.
+pub fn mnemonic_to_seed<S: AsRef<str>>(words: &[S], passphrase: &str) -> [u8; 64] {
+    let sentence = words
+        .iter()
+        .map(|w| w.as_ref())
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let salt = format!("mnemonic{}", passphrase);
+    let mut seed = [0u8; 64];
+    seed.copy_from_slice(&pbkdf2_hmac_sha512(
+        sentence.as_bytes(),
+        salt.as_bytes(),
+        2048,
+        64,
+    ));
+    seed
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hd::ExtendedKey;
+    use crate::hex;
+
+    // (entropy, mnemonic, seed with passphrase "TREZOR") from the official
+    // English test vectors
+    const VECTORS: [(&str, &str, &str); 8] = [
+        (
+            "00000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+        ),
+        (
+            "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+        ),
+        (
+            "80808080808080808080808080808080",
+            "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+            "d71de856f81a8acc65e6fc851a38d4d7ec216fd0796d0a6827a3ad6ed5511a30fa280f12eb2e47ed2ac03b5c462a0358d18d69fe4f985ec81778c1b370b652a8",
+        ),
+        (
+            "ffffffffffffffffffffffffffffffff",
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+            "ac27495480225222079d7be181583751e86f571027b0497b5b5d11218e0a8a13332572917f0f8e5a589620c6f15b11c61dee327651a14c34e18231052e48c069",
+        ),
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art",
+            "bda85446c68413707090a52022edd26a1c9462295029f2e60cd7c4f2bbd3097170af7a4d73245cafa9c3cca8d561a7c3de6f5d4a10be8ed2a5e608d68f92fcc8",
+        ),
+        (
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+            "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad",
+        ),
+        (
+            "066dca1a2bb7e8a1db2832148ce9933eea0f3ac9548d793112d9a95c9407efad",
+            "all hour make first leader extend hole alien behind guard gospel lava path output census museum junior mass reopen famous sing advance salt reform",
+            "26e975ec644423f4a4c4f4215ef09b4bd7ef924e85d1d17c4cf3f136c2863cf6df0a475045652c57eb5fb41513ca2a2d67722b77e954b4b3fc11f7590449191d",
+        ),
+        (
+            "f585c11aec520db57dd353c69554b21a89b20fb0650966fa0a9d6f74fd989d8f",
+            "void come effort suffer camp survey warrior heavy shoot primary clutch crush open amazing screen patrol group space point ten exist slush involve unfold",
+            "01f5bced59dec48e362f2c45b5de68b9fd6c92c6634f44d6d40aab69056506f0e35524a518034ddc1192e1dacd32c1ed3eaa3c3b131c88ed8e7e54c49a5d0998",
+        ),
+    ];
+
+    #[test]
+    fn test_wordlist() {
+        let words: Vec<&str> = ENGLISH.lines().collect();
+        assert_eq!(words.len(), 2048);
+        assert_eq!(words[0], "abandon");
+        assert_eq!(words[2047], "zoo");
+    }
+
+    #[test]
+    fn test_entropy_to_mnemonic() {
+        for (entropy, mnemonic, _) in VECTORS.iter() {
+            assert_eq!(
+                entropy_to_mnemonic(&hex::decode(entropy).unwrap())
+                    .unwrap()
+                    .join(" "),
+                *mnemonic
+            );
+        }
+        assert!(entropy_to_mnemonic(&[0; 15]).is_err());
+        assert!(entropy_to_mnemonic(&[0; 18]).is_err());
+        assert!(entropy_to_mnemonic(&[0; 36]).is_err());
+    }
+
+    #[test]
+    fn test_mnemonic_to_seed() {
+        for (_, mnemonic, seed) in VECTORS.iter() {
+            let words: Vec<&str> = mnemonic.split(' ').collect();
+            assert_eq!(hex::encode(&mnemonic_to_seed(&words, "TREZOR")), *seed);
+        }
+    }
+
+    #[test]
+    fn test_seed_to_master_key() {
+        // BIP39 vector 1 continues into BIP32 with this master key
+        let words = entropy_to_mnemonic(&[0; 16]).unwrap();
+        let master = ExtendedKey::from_seed(&mnemonic_to_seed(&words, "TREZOR")).unwrap();
+        assert_eq!(
+            master.xprv(false).unwrap(),
+            "xprv9s21ZrQH143K3h3fDYiay8mocZ3afhfULfb5GX8kCBdno77K4HiA15Tg23wpbeF1pLfs1c5SPmYHrEpTuuRhxMwvKDwqdKiGJS9XFKzUsAF"
+        );
+    }
+}
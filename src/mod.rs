@@ -1,2 +0,0 @@
-pub mod ecc;
-pub mod errors;
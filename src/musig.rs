@@ -0,0 +1,151 @@
+//! MuSig2 key aggregation from BIP327. Only `KeyAgg` is covered: tweaking
+//! the aggregate key and the nonce and signing rounds are not.
+
+use crate::ecc::{FieldElement, FieldElementOps};
+use crate::ecdsa::PublicKey;
+use crate::errors::ValueError;
+use crate::hash::tagged_hash;
+use crate::point::{Point, PointOps};
+use crate::secp256k1;
+use ibig::{ubig, UBig};
+
+/// The first key that differs from the first one, compressed; 33 zero
+/// bytes when all keys are equal.
+fn second_key(keys: &[Vec<u8>]) -> Vec<u8> {
+    keys.iter()
+        .find(|key| **key != keys[0])
+        .cloned()
+        .unwrap_or_else(|| vec![0u8; 33])
+}
+
+/// Aggregates `pubkeys` into the MuSig2 key `Q = sum(a_i * P_i)`, returning
+/// `Q` and the coefficients `a_i` (mod N) in input order. With
+/// `L = H_KeyAgg list(P_1 || ... || P_u)` over the compressed keys,
+/// `a_i = H_KeyAgg coefficient(L || P_i)`, except that every copy of the
+/// second distinct key gets `a_i = 1`, which saves a multiplication when
+/// signing. The order of `pubkeys` matters.
+///
+/// Fails on an empty list, a key that is not a valid secp256k1 public key,
+/// or an aggregate at infinity.
+pub fn aggregate_keys(pubkeys: &[Point]) -> Result<(Point, Vec<FieldElement>), ValueError> {
+    if pubkeys.is_empty() {
+        return Err(ValueError {
+            message: "no keys to aggregate".to_string(),
+        });
+    }
+    if let Some(i) = pubkeys.iter().position(|key| !key.is_valid_public_key()) {
+        return Err(ValueError {
+            message: format!("key {} is not a valid public key", i),
+        });
+    }
+    let keys = pubkeys
+        .iter()
+        .map(|key| key.sec(true))
+        .collect::<Result<Vec<Vec<u8>>, _>>()?;
+    let list_hash = tagged_hash("KeyAgg list", &keys.concat());
+    let second = second_key(&keys);
+    let n = secp256k1::n();
+    let coefficients: Vec<FieldElement> = keys
+        .iter()
+        .map(|key| {
+            let a = match *key == second {
+                true => ubig!(1),
+                false => {
+                    let mut data = list_hash.to_vec();
+                    data.extend_from_slice(key);
+                    UBig::from_be_bytes(&tagged_hash("KeyAgg coefficient", &data))
+                }
+            };
+            FieldElement::from_reduced(a, n.clone())
+        })
+        .collect();
+    let terms: Vec<Point> = pubkeys
+        .iter()
+        .zip(coefficients.iter())
+        .map(|(key, a)| key.mul_glv(&a.num))
+        .collect();
+    Ok((PublicKey::combine(&terms)?, coefficients))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hex;
+    use crate::schnorr::x_bytes;
+
+    fn key(sec: &str) -> Point {
+        Point::parse(&hex::decode(sec).unwrap()).unwrap()
+    }
+
+    // the valid keys of the BIP327 key_agg_vectors.json
+    fn vector_keys() -> [Point; 3] {
+        [
+            key("02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"),
+            key("03dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659"),
+            key("023590a94e768f8e1815c2f24b4d80a8e3149316c3518ce7b7ad338368d038ca66"),
+        ]
+    }
+
+    #[test]
+    fn test_aggregate_keys() {
+        let keys = vector_keys();
+        let cases: [(&[usize], &str); 4] = [
+            (
+                &[0, 1, 2],
+                "90539eede565f5d054f32cc0c220126889ed1e5d193baf15aef344fe59d4610c",
+            ),
+            (
+                &[2, 1, 0],
+                "6204de8b083426dc6eaf9502d27024d53fc826bf7d2012148a0575435df54b2b",
+            ),
+            (
+                &[0, 0, 0],
+                "b436e3bad62b8cd409969a224731c193d051162d8c5ae8b109306127da3aa935",
+            ),
+            (
+                &[0, 0, 1, 1],
+                "69bc22bfa5d106306e48a20679de1d7389386124d07571d0d872686028c26a3e",
+            ),
+        ];
+        for (indices, expected) in cases.iter() {
+            let pubkeys: Vec<Point> = indices.iter().map(|i| keys[*i].clone()).collect();
+            let (q, coefficients) = aggregate_keys(&pubkeys).unwrap();
+            assert_eq!(hex::encode(&x_bytes(&q)), *expected);
+            assert_eq!(coefficients.len(), pubkeys.len());
+        }
+    }
+
+    #[test]
+    fn test_coefficients() {
+        let keys = vector_keys();
+        let (q, coefficients) = aggregate_keys(&keys).unwrap();
+        // the second key is the one left unweighted
+        assert!(coefficients[1].is_one());
+        assert!(!coefficients[0].is_one() && !coefficients[2].is_one());
+        let sum = keys.iter().zip(coefficients.iter()).fold(
+            Point::infinity(secp256k1::a(), secp256k1::b()),
+            |acc, (key, a)| &acc + &(key * &a.num),
+        );
+        assert_eq!(sum, q);
+        // with no second distinct key, nothing is unweighted
+        let (_, coefficients) = aggregate_keys(&[keys[0].clone(), keys[0].clone()]).unwrap();
+        assert!(!coefficients[0].is_one());
+        assert_eq!(coefficients[0], coefficients[1]);
+    }
+
+    #[test]
+    fn test_aggregate_keys_invalid() {
+        assert_eq!(
+            aggregate_keys(&[]).unwrap_err().message,
+            "no keys to aggregate"
+        );
+        let keys = vector_keys();
+        let infinity = Point::infinity(secp256k1::a(), secp256k1::b());
+        assert_eq!(
+            aggregate_keys(&[keys[0].clone(), infinity])
+                .unwrap_err()
+                .message,
+            "key 1 is not a valid public key"
+        );
+    }
+}
@@ -0,0 +1,958 @@
+use crate::base58;
+use crate::ecc::{FieldElement, FieldElementOps};
+use crate::errors::{EccError, ValueError};
+use crate::hash::hash160;
+use crate::hex;
+use crate::jacobian::JacobianPoint;
+use crate::secp256k1;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::cmp::{Ordering, PartialEq};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::{Add, Mul, Neg, Sub};
+use core::str::FromStr;
+use ibig::ops::UnsignedAbs;
+use ibig::{IBig, UBig};
+
+/// A point on the curve `y^2 = x^3 + a*x + b`. `None` coordinates represent
+/// the point at infinity.
+#[derive(Clone, Debug)]
+pub struct Point {
+    pub x: Option<FieldElement>,
+    pub y: Option<FieldElement>,
+    pub a: FieldElement,
+    pub b: FieldElement,
+}
+
+pub trait PointOps: Sized {
+    fn new(
+        x: Option<FieldElement>,
+        y: Option<FieldElement>,
+        a: FieldElement,
+        b: FieldElement,
+    ) -> Result<Self, EccError>;
+    fn infinity(a: FieldElement, b: FieldElement) -> Self;
+    fn is_infinity(&self) -> bool;
+    fn scalar_mul(&self, coeff: &UBig) -> Self;
+    /// Montgomery-ladder scalar multiplication. Every bit position up to the
+    /// bit length of the field prime (or of `coeff`, if longer) costs one
+    /// addition and one doubling, and the two ladder points are exchanged
+    /// with a masked `conditional_swap` rather than a branch on the bit.
+    /// That is the whole guarantee: Jacobian addition and doubling return
+    /// early on the point at infinity, which one ladder point is until the
+    /// highest set bit of `coeff`, so timing still reveals its bit length,
+    /// and `UBig` arithmetic is not constant-time at all.
+    fn scalar_mul_ct(&self, coeff: &UBig) -> Self;
+    /// The SEC encoding, 33 bytes compressed or 65 uncompressed. The point
+    /// at infinity has none and is an error.
+    fn sec(&self, compressed: bool) -> Result<Vec<u8>, EccError>;
+    fn parse(sec: &[u8]) -> Result<Self, ValueError>;
+    /// The legacy P2PKH address of this key; an error for the point at
+    /// infinity, like `sec`.
+    fn address(&self, compressed: bool, testnet: bool) -> Result<String, EccError>;
+    fn to_jacobian(&self) -> JacobianPoint;
+    /// Computes `u*g + v*p` with Shamir's trick: both scalars are walked
+    /// together from the top bit, so the two multiplications share one chain
+    /// of `max(u.bit_len(), v.bit_len())` doublings instead of needing one
+    /// chain each.
+    fn mul_add(u: &UBig, g: &Self, v: &UBig, p: &Self) -> Self;
+    /// `k * self` on secp256k1 via the GLV endomorphism: with
+    /// `k = k1 + k2*lambda`, the product is `k1*P + k2*(beta*x, y)`, two
+    /// 128-bit multiplications that `mul_add` runs over one shared chain of
+    /// doublings, half as long as a direct multiplication needs. Not
+    /// constant time.
+    ///
+    /// Panics if the point is not on secp256k1.
+    fn mul_glv(&self, k: &UBig) -> Self;
+    /// Whether this is usable as a secp256k1 public key: a finite point
+    /// satisfying the curve equation whose order divides `N`.
+    fn is_valid_public_key(&self) -> bool;
+    /// BIP340 `lift_x`: the secp256k1 point with x-coordinate `x` and an
+    /// even y. Fails if `x` is not in the secp256k1 base field or
+    /// `x^3 + 7` has no square root there.
+    fn lift_x(x: &FieldElement) -> Result<Self, ValueError>;
+}
+
+impl PartialEq for Point {
+    fn eq(&self, other: &Point) -> bool {
+        self.x == other.x && self.y == other.y && self.a == other.a && self.b == other.b
+    }
+}
+
+impl Eq for Point {}
+
+impl Hash for Point {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.x.hash(state);
+        self.y.hash(state);
+        self.a.hash(state);
+        self.b.hash(state);
+    }
+}
+
+/// Compressed SEC as hex, or `infinity` for the identity.
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self.sec(true) {
+            Ok(sec) => write!(f, "{}", hex::encode(&sec)),
+            Err(_) => write!(f, "infinity"),
+        }
+    }
+}
+
+/// Parses the `Display` form back into a secp256k1 point. Uncompressed SEC
+/// hex is accepted too.
+impl FromStr for Point {
+    type Err = ValueError;
+    fn from_str(s: &str) -> Result<Point, ValueError> {
+        match s {
+            "infinity" => Ok(Point::infinity(secp256k1::a(), secp256k1::b())),
+            _ => Point::parse(&hex::decode(s)?),
+        }
+    }
+}
+
+impl<'a> Add<&'a Point> for &'a Point {
+    type Output = Point;
+    fn add(self, rhs: &Point) -> Point {
+        assert!(
+            self.a == rhs.a && self.b == rhs.b,
+            "points are not on the same curve"
+        );
+        let (x1, y1, x2, y2) = match (&self.x, &self.y, &rhs.x, &rhs.y) {
+            (Some(x1), Some(y1), Some(x2), Some(y2)) => (x1, y1, x2, y2),
+            // the point at infinity is the identity
+            _ if self.is_infinity() => return rhs.clone(),
+            _ => return self.clone(),
+        };
+        let s = if x1 != x2 {
+            // chord through two distinct points
+            &(y2 - y1) / &(x2 - x1)
+        } else if y1 != y2 || y1.is_zero() {
+            // P + (-P), or a vertical tangent
+            return Point::infinity(self.a.clone(), self.b.clone());
+        } else {
+            // tangent at P
+            &(&(x1 * x1).mul_small(3) + &self.a) / &y1.double()
+        };
+        let x3 = &(&(&s * &s) - x1) - x2;
+        let y3 = &(&s * &(x1 - &x3)) - y1;
+        Point {
+            x: Some(x3),
+            y: Some(y3),
+            a: self.a.clone(),
+            b: self.b.clone(),
+        }
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+    fn add(self, rhs: Self) -> Self {
+        &self + &rhs
+    }
+}
+
+impl<'a> Mul<&'a UBig> for &'a Point {
+    type Output = Point;
+    fn mul(self, coeff: &UBig) -> Point {
+        self.scalar_mul(coeff)
+    }
+}
+
+impl Mul<UBig> for Point {
+    type Output = Point;
+    fn mul(self, coeff: UBig) -> Self {
+        self.scalar_mul(&coeff)
+    }
+}
+
+impl Neg for &Point {
+    type Output = Point;
+    fn neg(self) -> Point {
+        // reflect across the x-axis; infinity is its own inverse
+        Point {
+            x: self.x.clone(),
+            y: self.y.as_ref().map(|y| -y),
+            a: self.a.clone(),
+            b: self.b.clone(),
+        }
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+    fn neg(self) -> Self {
+        -&self
+    }
+}
+
+impl<'a> Sub<&'a Point> for &'a Point {
+    type Output = Point;
+    fn sub(self, rhs: &Point) -> Point {
+        self + &(-rhs)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+    fn sub(self, rhs: Self) -> Self {
+        &self - &rhs
+    }
+}
+
+impl PointOps for Point {
+    fn new(
+        x: Option<FieldElement>,
+        y: Option<FieldElement>,
+        a: FieldElement,
+        b: FieldElement,
+    ) -> Result<Point, EccError> {
+        match (&x, &y) {
+            (None, None) => Ok(Point::infinity(a, b)),
+            (Some(x_num), Some(y_num)) => match is_on_curve(x_num, y_num, &a, &b) {
+                true => Ok(Point { x, y, a, b }),
+                false => Err(EccError::NotOnCurve),
+            },
+            // a point needs both coordinates, or neither for infinity
+            _ => Err(EccError::NotOnCurve),
+        }
+    }
+    fn infinity(a: FieldElement, b: FieldElement) -> Point {
+        Point {
+            x: None,
+            y: None,
+            a,
+            b,
+        }
+    }
+    fn is_infinity(&self) -> bool {
+        self.x.is_none()
+    }
+    fn scalar_mul(&self, coeff: &UBig) -> Point {
+        // double-and-add, walking the bits of coeff from least significant up
+        let mut current = self.clone();
+        let mut result = Point::infinity(self.a.clone(), self.b.clone());
+        for i in 0..coeff.bit_len() {
+            if coeff.bit(i) {
+                result = &result + &current;
+            }
+            current = &current + &current;
+        }
+        result
+    }
+    fn scalar_mul_ct(&self, coeff: &UBig) -> Point {
+        let bits = self.a.prime.bit_len().max(coeff.bit_len());
+        // invariant: r1 - r0 == self
+        let mut r0 = JacobianPoint::infinity(self.a.clone(), self.b.clone());
+        let mut r1 = self.to_jacobian();
+        // swapped around each step when the bit is set, so the same
+        // add-then-double runs whichever way it goes; back-to-back swaps
+        // cancel, leaving one per change of bit
+        let mut swapped = false;
+        for i in (0..bits).rev() {
+            let bit = coeff.bit(i);
+            r0.conditional_swap(&mut r1, bit ^ swapped);
+            swapped = bit;
+            r1 = &r0 + &r1;
+            r0 = r0.double();
+        }
+        r0.conditional_swap(&mut r1, swapped);
+        r0.to_affine()
+    }
+    fn sec(&self, compressed: bool) -> Result<Vec<u8>, EccError> {
+        let (x, y) = match (&self.x, &self.y) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return Err(EccError::PointAtInfinity),
+        };
+        let mut out = Vec::with_capacity(65);
+        if compressed {
+            // the prefix records the parity of y, which parse recovers via sqrt
+            out.push(if y.num.bit(0) { 0x03 } else { 0x02 });
+            out.extend(x.to_be_bytes(32));
+        } else {
+            out.push(0x04);
+            out.extend(x.to_be_bytes(32));
+            out.extend(y.to_be_bytes(32));
+        }
+        Ok(out)
+    }
+    fn parse(sec: &[u8]) -> Result<Point, ValueError> {
+        let expected_len = match sec.first() {
+            Some(0x04) => 65,
+            Some(0x02) | Some(0x03) => 33,
+            Some(prefix) => {
+                return Err(ValueError {
+                    message: format!("unknown SEC prefix {:#04x}", prefix),
+                })
+            }
+            None => {
+                return Err(ValueError {
+                    message: "empty SEC public key".to_string(),
+                })
+            }
+        };
+        if sec.len() != expected_len {
+            return Err(ValueError {
+                message: format!(
+                    "SEC prefix {:#04x} needs {} bytes, got {}",
+                    sec[0],
+                    expected_len,
+                    sec.len()
+                ),
+            });
+        }
+        let x = FieldElement::from_be_bytes(&sec[1..33], secp256k1::p())?;
+        let y = match sec[0] {
+            0x04 => FieldElement::from_be_bytes(&sec[33..65], secp256k1::p())?,
+            // 0x02 or 0x03, as checked above
+            _ => {
+                let alpha = x.clone().pow(3) + secp256k1::b();
+                let beta = secp256k1::sqrt(&alpha).ok_or(EccError::NotOnCurve)?;
+                match beta.num.bit(0) == (sec[0] == 0x03) {
+                    true => beta,
+                    false => -beta,
+                }
+            }
+        };
+        let point = Point::new(Some(x), Some(y), secp256k1::a(), secp256k1::b())?;
+        match point.is_valid_public_key() {
+            true => Ok(point),
+            false => Err(ValueError {
+                message: "public key is not in the secp256k1 group".to_string(),
+            }),
+        }
+    }
+    fn address(&self, compressed: bool, testnet: bool) -> Result<String, EccError> {
+        // legacy P2PKH: version byte, then hash160 of the SEC public key
+        let mut payload = vec![if testnet { 0x6f } else { 0x00 }];
+        payload.extend_from_slice(&hash160(&self.sec(compressed)?));
+        Ok(base58::encode_check(&payload))
+    }
+    fn to_jacobian(&self) -> JacobianPoint {
+        match (&self.x, &self.y) {
+            (Some(x), Some(y)) => JacobianPoint {
+                x: x.clone(),
+                y: y.clone(),
+                z: FieldElement::from_u64(1, x.prime.clone()),
+                a: self.a.clone(),
+                b: self.b.clone(),
+            },
+            _ => JacobianPoint::infinity(self.a.clone(), self.b.clone()),
+        }
+    }
+    fn mul_glv(&self, k: &UBig) -> Point {
+        assert!(
+            self.a == secp256k1::a() && self.b == secp256k1::b(),
+            "GLV needs a secp256k1 point"
+        );
+        let endo = Point {
+            x: self.x.as_ref().map(|x| x * &secp256k1::beta()),
+            y: self.y.clone(),
+            a: self.a.clone(),
+            b: self.b.clone(),
+        };
+        let (k1, k2) = secp256k1::glv_split(k);
+        // fold each sign into its point so both scalars are non-negative
+        let (p1, k1) = match k1 < IBig::from(0u8) {
+            true => (-self, k1.unsigned_abs()),
+            false => (self.clone(), k1.unsigned_abs()),
+        };
+        let (p2, k2) = match k2 < IBig::from(0u8) {
+            true => (-&endo, k2.unsigned_abs()),
+            false => (endo, k2.unsigned_abs()),
+        };
+        Point::mul_add(&k1, &p1, &k2, &p2)
+    }
+    fn mul_add(u: &UBig, g: &Point, v: &UBig, p: &Point) -> Point {
+        let (g, p) = (g.to_jacobian(), p.to_jacobian());
+        let both = &g + &p;
+        let mut result = JacobianPoint::infinity(g.a.clone(), g.b.clone());
+        for i in (0..u.bit_len().max(v.bit_len())).rev() {
+            result = result.double();
+            match (u.bit(i), v.bit(i)) {
+                (true, true) => result = &result + &both,
+                (true, false) => result = &result + &g,
+                (false, true) => result = &result + &p,
+                (false, false) => {}
+            }
+        }
+        result.to_affine()
+    }
+    fn is_valid_public_key(&self) -> bool {
+        let (x, y) = match (&self.x, &self.y) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return false,
+        };
+        if self.a != secp256k1::a() || self.b != secp256k1::b() {
+            return false;
+        }
+        if !is_on_curve(x, y, &self.a, &self.b) {
+            return false;
+        }
+        // secp256k1 has cofactor 1, so this cannot fail for a point on the
+        // curve today; it keeps the check honest rather than assumed
+        self.to_jacobian().scalar_mul(&secp256k1::n()).is_infinity()
+    }
+    fn lift_x(x: &FieldElement) -> Result<Point, ValueError> {
+        if x.prime != secp256k1::p() {
+            return Err(ValueError {
+                message: "x is not a secp256k1 field element".to_string(),
+            });
+        }
+        let y =
+            secp256k1::sqrt(&(x.clone().pow(3) + secp256k1::b())).ok_or(EccError::NotOnCurve)?;
+        let y = match y.num.bit(0) {
+            true => -y,
+            false => y,
+        };
+        Ok(Point {
+            x: Some(x.clone()),
+            y: Some(y),
+            a: secp256k1::a(),
+            b: secp256k1::b(),
+        })
+    }
+}
+
+/// Whether `sec` is a compressed (`0x02`/`0x03`, 33 bytes) or uncompressed
+/// (`0x04`, 65 bytes) SEC encoding, judged by prefix and length alone;
+/// `None` for anything else. The coordinates are not checked.
+pub fn sec_is_compressed(sec: &[u8]) -> Option<bool> {
+    match (sec.first(), sec.len()) {
+        (Some(0x02), 33) | (Some(0x03), 33) => Some(true),
+        (Some(0x04), 65) => Some(false),
+        _ => None,
+    }
+}
+
+const NO_SEC: &str = "the point at infinity has no SEC encoding";
+
+/// Compares two public keys by their compressed SEC encoding, the order
+/// BIP67 multisig and BIP327 `KeySort` use. Panics on the point at
+/// infinity, which has no SEC encoding.
+pub fn cmp_sec(a: &Point, b: &Point) -> Ordering {
+    let sec = |key: &Point| key.sec(true).expect(NO_SEC);
+    sec(a).cmp(&sec(b))
+}
+
+/// Sorts `keys` with `cmp_sec`, so that everyone building a multisig
+/// script from the same keys lists them in the same order.
+pub fn sort_keys(keys: &mut [Point]) {
+    keys.sort_by_cached_key(|key| key.sec(true).expect(NO_SEC));
+}
+
+/// Whether `(x, y)` satisfies `y^2 = x^3 + a*x + b`, without building a
+/// `Point`. All four elements must share a field; otherwise the answer is
+/// `false`.
+pub fn is_on_curve(x: &FieldElement, y: &FieldElement, a: &FieldElement, b: &FieldElement) -> bool {
+    if [y, a, b].iter().any(|fe| fe.prime != x.prime) {
+        return false;
+    }
+    y * y == &(&(x * x) * x) + &(&(a * x) + b)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hex;
+    use ibig::ubig;
+    use proptest::prelude::*;
+
+    fn fe(num: u32) -> FieldElement {
+        FieldElement::new(UBig::from(num), ubig!(223)).unwrap()
+    }
+
+    #[test]
+    fn test_on_curve() {
+        let valid = [(192, 105), (17, 56), (1, 193)];
+        let invalid = [(200, 119), (42, 99)];
+        for (x, y) in valid.iter() {
+            assert!(Point::new(Some(fe(*x)), Some(fe(*y)), fe(0), fe(7)).is_ok());
+        }
+        for (x, y) in invalid.iter() {
+            assert_eq!(
+                Point::new(Some(fe(*x)), Some(fe(*y)), fe(0), fe(7)),
+                Err(EccError::NotOnCurve)
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_on_curve() {
+        // every (x, y) over F_223 against the equation in plain integers,
+        // on a curve with non-zero a
+        for x in 0..223u32 {
+            let rhs = (x * x * x + 2 * x + 3) % 223;
+            for y in 0..223u32 {
+                assert_eq!(
+                    is_on_curve(&fe(x), &fe(y), &fe(2), &fe(3)),
+                    y * y % 223 == rhs
+                );
+            }
+        }
+        let g = secp256k1::g();
+        let (x, y) = (g.x.unwrap(), g.y.unwrap());
+        assert!(is_on_curve(&x, &y, &g.a, &g.b));
+        assert!(!is_on_curve(&y, &x, &g.a, &g.b));
+        // (192, 105) is on y^2 = x^3 + 7 over F_223, but not across fields
+        assert!(is_on_curve(&fe(192), &fe(105), &fe(0), &fe(7)));
+        assert!(!is_on_curve(&fe(192), &fe(105), &g.a, &g.b));
+    }
+
+    #[test]
+    fn test_infinity() {
+        let inf = Point::new(None, None, fe(0), fe(7)).unwrap();
+        assert!(inf.is_infinity());
+        assert_eq!(inf, Point::infinity(fe(0), fe(7)));
+        assert!(Point::new(Some(fe(192)), None, fe(0), fe(7)).is_err());
+        assert!(Point::new(None, Some(fe(105)), fe(0), fe(7)).is_err());
+    }
+
+    fn point(x: u32, y: u32) -> Point {
+        Point::new(Some(fe(x)), Some(fe(y)), fe(0), fe(7)).unwrap()
+    }
+
+    #[test]
+    fn test_add() {
+        let additions = [
+            ((170, 142), (60, 139), (220, 181)),
+            ((47, 71), (17, 56), (215, 68)),
+            ((143, 98), (76, 66), (47, 71)),
+        ];
+        for (p1, p2, p3) in additions.iter() {
+            assert_eq!(point(p1.0, p1.1) + point(p2.0, p2.1), point(p3.0, p3.1));
+        }
+    }
+
+    #[test]
+    fn test_add_identity() {
+        let p = point(192, 105);
+        let inf = Point::infinity(fe(0), fe(7));
+        assert_eq!(&p + &inf, p);
+        assert_eq!(&inf + &p, p);
+        assert_eq!(&inf + &inf, inf);
+        // (192, 105) + (192, 223 - 105) is P + (-P)
+        assert_eq!(&p + &point(192, 118), inf);
+    }
+
+    #[test]
+    fn test_double() {
+        let doublings = [
+            ((192, 105), (49, 71)),
+            ((143, 98), (64, 168)),
+            ((47, 71), (36, 111)),
+        ];
+        for (p1, p2) in doublings.iter() {
+            let p = point(p1.0, p1.1);
+            assert_eq!(&p + &p, point(p2.0, p2.1));
+        }
+    }
+
+    #[test]
+    fn test_double_vertical_tangent() {
+        // y^2 = x^3 + 1 over F_7 contains (3, 0), whose tangent is vertical
+        let fe7 = |num: u32| FieldElement::new(UBig::from(num), ubig!(7)).unwrap();
+        let p = Point::new(Some(fe7(3)), Some(fe7(0)), fe7(0), fe7(1)).unwrap();
+        assert!((&p + &p).is_infinity());
+    }
+
+    #[test]
+    fn test_scalar_mul() {
+        let p = point(47, 71);
+        assert!(p.scalar_mul(&ubig!(0)).is_infinity());
+        assert_eq!(p.scalar_mul(&ubig!(1)), p);
+        assert_eq!(p.scalar_mul(&ubig!(2)), &p + &p);
+        assert_eq!(&p * &ubig!(3), &(&p + &p) + &p);
+        assert_eq!(p.clone() * ubig!(8), point(116, 55));
+        // (47, 71) generates a group of order 21
+        assert!((&p * &ubig!(21)).is_infinity());
+        assert_eq!(&p * &ubig!(22), p);
+        let mut sum = Point::infinity(fe(0), fe(7));
+        for k in 0..21u32 {
+            assert_eq!(&p * &UBig::from(k), sum);
+            sum = &sum + &p;
+        }
+    }
+
+    #[test]
+    fn test_scalar_mul_ct() {
+        let p = point(47, 71);
+        for k in 0..=22u32 {
+            let k = UBig::from(k);
+            assert_eq!(p.scalar_mul_ct(&k), p.scalar_mul(&k));
+        }
+        let g = secp256k1::g();
+        let k = secp256k1::n() - ubig!(2);
+        assert_eq!(g.scalar_mul_ct(&k), g.scalar_mul(&k));
+        assert!(g.scalar_mul_ct(&secp256k1::n()).is_infinity());
+    }
+
+    #[test]
+    fn test_sec() {
+        let cases = [
+            (
+                ubig!(5000),
+                "04ffe558e388852f0120e46af2d1b370f85854a8eb0841811ece0e3e03d282d57c315dc72890a4f10a1481c031b03b351b0dc79901ca18a00cf009dbdb157a1d10",
+                "02ffe558e388852f0120e46af2d1b370f85854a8eb0841811ece0e3e03d282d57c",
+            ),
+            (
+                ubig!(2018).pow(5),
+                "04027f3da1918455e03c46f659266a1bb5204e959db7364d2f473bdf8f0a13cc9dff87647fd023c13b4a4994f17691895806e1b40b57f4fd22581a4f46851f3b06",
+                "02027f3da1918455e03c46f659266a1bb5204e959db7364d2f473bdf8f0a13cc9d",
+            ),
+            (
+                ubig!(0xdeadbeef12345),
+                "04d90cd625ee87dd38656dd95cf79f65f60f7273b67d3096e68bd81e4f5342691f842efa762fd59961d0e99803c61edba8b3e3f7dc3a341836f97733aebf987121",
+                "03d90cd625ee87dd38656dd95cf79f65f60f7273b67d3096e68bd81e4f5342691f",
+            ),
+        ];
+        for (secret, uncompressed, compressed) in cases.iter() {
+            let point = &secp256k1::g() * secret;
+            let sec = point.sec(false).unwrap();
+            assert_eq!(hex::encode(&sec), *uncompressed);
+            assert_eq!(Point::parse(&sec).unwrap(), point);
+            let sec = point.sec(true).unwrap();
+            assert_eq!(hex::encode(&sec), *compressed);
+            assert_eq!(Point::parse(&sec).unwrap(), point);
+        }
+    }
+
+    #[test]
+    fn test_address() {
+        // (secret, [uncompressed mainnet, uncompressed testnet, compressed mainnet, compressed testnet])
+        let cases = [
+            (
+                ubig!(5002),
+                [
+                    "16wSJUKH9aMz7Fx9E6iiV9oR4eHaMFaWCB",
+                    "mmTPbXQFxboEtNRkwfh6K51jvdtHLxGeMA",
+                    "1BMERxWc9yF8gYAob2FkngUJgnHuB8AUaN",
+                    "mqsBj1baxzgPTeeRJbE8cbgdYmtc3yess3",
+                ],
+            ),
+            (
+                ubig!(2020).pow(5),
+                [
+                    "1Eg8Q4ykzLzTF4FKHh2YcEDRXEva4Ka2PB",
+                    "muC5h84joNRi2Aiw1FzvS9RkPEXGwaoxGE",
+                    "19JYTuj9fg6aeS7apjuDpJoN9g7Y9ztYXT",
+                    "mopVkxp8UhXqRYbCYJsbeE1h1fiF64jcoH",
+                ],
+            ),
+            (
+                ubig!(0x12345deadbeef),
+                [
+                    "1WQWFhHgTg3Y8kyEF8cVw71EgdAsYzAZa",
+                    "mg2MoJnGVV7JKFEawp6zKrKL6gDsoXJJRA",
+                    "1F1Pn2y6pDb68E5nYJJeba4TLg2U7B6KF1",
+                    "muXM5645dF2LuLZQFsH2RVGnCfdB4vR1bB",
+                ],
+            ),
+        ];
+        for (secret, addresses) in cases.iter() {
+            let point = &secp256k1::g() * secret;
+            assert_eq!(point.address(false, false).unwrap(), addresses[0]);
+            assert_eq!(point.address(false, true).unwrap(), addresses[1]);
+            assert_eq!(point.address(true, false).unwrap(), addresses[2]);
+            assert_eq!(point.address(true, true).unwrap(), addresses[3]);
+        }
+    }
+
+    #[test]
+    fn test_ne() {
+        let a = Point::new(Some(fe(192)), Some(fe(105)), fe(0), fe(7)).unwrap();
+        let b = Point::new(Some(fe(17)), Some(fe(56)), fe(0), fe(7)).unwrap();
+        assert_eq!(a, a.clone());
+        assert!(a != b);
+    }
+
+    #[test]
+    fn test_mul_glv() {
+        let g = secp256k1::g();
+        let n = secp256k1::n();
+        let p = g.scalar_mul(&ubig!(0xdeadbeef));
+        let mut scalars = vec![ubig!(1), ubig!(2), &n - ubig!(1), secp256k1::lambda()];
+        let mut seed = crate::hash::sha256(b"mul glv");
+        for _ in 0..6 {
+            scalars.push(UBig::from_be_bytes(&seed) % &n);
+            seed = crate::hash::sha256(&seed);
+        }
+        for k in scalars.iter() {
+            assert_eq!(g.mul_glv(k), g.scalar_mul(k));
+            assert_eq!(p.mul_glv(k), p.scalar_mul(k));
+        }
+        assert!(g.mul_glv(&ubig!(0)).is_infinity());
+        assert!(g.mul_glv(&n).is_infinity());
+        let inf = Point::infinity(secp256k1::a(), secp256k1::b());
+        assert!(inf.mul_glv(&ubig!(5)).is_infinity());
+    }
+
+    #[test]
+    #[should_panic(expected = "GLV needs a secp256k1 point")]
+    fn test_mul_glv_other_curve() {
+        point(47, 71).mul_glv(&ubig!(3));
+    }
+
+    #[test]
+    fn test_display_from_str() {
+        let g = secp256k1::g();
+        let shown = g.to_string();
+        assert_eq!(
+            shown,
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+        );
+        assert_eq!(shown.parse::<Point>().unwrap(), g);
+        let inf = Point::infinity(secp256k1::a(), secp256k1::b());
+        assert_eq!(inf.to_string(), "infinity");
+        assert_eq!("infinity".parse::<Point>().unwrap(), inf);
+        let uncompressed = hex::encode(&g.sec(false).unwrap());
+        assert_eq!(uncompressed.parse::<Point>().unwrap(), g);
+        assert!("02zz".parse::<Point>().is_err());
+        assert!("Infinity".parse::<Point>().is_err());
+    }
+
+    #[test]
+    fn test_is_valid_public_key() {
+        let g = secp256k1::g();
+        assert!(g.is_valid_public_key());
+        assert!(!Point::infinity(secp256k1::a(), secp256k1::b()).is_valid_public_key());
+        // off the curve: y nudged by one
+        let off_curve = Point {
+            y: Some(g.y.clone().unwrap() + secp256k1::s256_field(ubig!(1)).unwrap()),
+            ..g.clone()
+        };
+        assert!(!off_curve.is_valid_public_key());
+        // a point of order 7 on y^2 = x^3 + 7 over F_223
+        assert!(!point(15, 86).is_valid_public_key());
+    }
+
+    #[test]
+    fn test_lift_x() {
+        let s256 = |h| FieldElement::from_hex(h, secp256k1::p()).unwrap();
+        // BIP340 test vector 0: the public key of secret 3
+        let point = Point::lift_x(&s256(
+            "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+        ))
+        .unwrap();
+        assert_eq!(
+            point.y.clone().unwrap(),
+            s256("388f7b0f632de8140fe337e62a37f3566500a99934c2231b6cb9fd7584b8e672")
+        );
+        assert_eq!(point, &secp256k1::g() * &ubig!(3));
+        // G itself already has an even y
+        assert_eq!(
+            Point::lift_x(secp256k1::g().x.as_ref().unwrap()).unwrap(),
+            secp256k1::g()
+        );
+        // BIP340 test vector 5: a public key not on the curve
+        assert_eq!(
+            Point::lift_x(&s256(
+                "eefdea4cdb677750a420fee807eacf21eb9898ae79b9768766e4faa04a2d4a34"
+            ))
+            .unwrap_err()
+            .message,
+            "point is not on the curve"
+        );
+        assert_eq!(
+            Point::lift_x(&fe(15)).unwrap_err().message,
+            "x is not a secp256k1 field element"
+        );
+    }
+
+    #[test]
+    fn test_sec_infinity() {
+        let inf = Point::infinity(secp256k1::a(), secp256k1::b());
+        assert_eq!(inf.sec(true), Err(EccError::PointAtInfinity));
+        assert_eq!(inf.sec(false), Err(EccError::PointAtInfinity));
+        assert_eq!(inf.address(true, false), Err(EccError::PointAtInfinity));
+        assert_eq!(inf.to_string(), "infinity");
+    }
+
+    #[test]
+    fn test_sec_is_compressed() {
+        let g = secp256k1::g();
+        assert_eq!(sec_is_compressed(&g.sec(true).unwrap()), Some(true));
+        assert_eq!(sec_is_compressed(&(-&g).sec(true).unwrap()), Some(true));
+        assert_eq!(sec_is_compressed(&g.sec(false).unwrap()), Some(false));
+        assert_eq!(sec_is_compressed(&g.sec(true).unwrap()[..32]), None);
+        assert_eq!(sec_is_compressed(&g.sec(false).unwrap()[..33]), None);
+        let mut wrong_prefix = g.sec(false).unwrap();
+        wrong_prefix[0] = 0x02;
+        assert_eq!(sec_is_compressed(&wrong_prefix), None);
+        assert_eq!(sec_is_compressed(&[0x05; 33]), None);
+        assert_eq!(sec_is_compressed(&[]), None);
+    }
+
+    proptest! {
+        // each parse checks the key's order with a full multiplication
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn prop_sec_round_trip(secret in any::<[u8; 32]>()) {
+            let k = UBig::from_be_bytes(&secret) % secp256k1::n();
+            prop_assume!(k != UBig::from(0u8));
+            let point = secp256k1::g().mul_glv(&k);
+            for compressed in [true, false] {
+                let sec = point.sec(compressed).unwrap();
+                prop_assert_eq!(sec_is_compressed(&sec), Some(compressed));
+                prop_assert_eq!(Point::parse(&sec).unwrap(), point.clone());
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let g = secp256k1::g();
+        let mut keys: Vec<Point> = (1..=5u32).map(|k| &g * &UBig::from(k)).collect();
+        // -G shares G's x, so only its 0x03 prefix moves it to the end
+        keys.push(-&g);
+        let expected = [
+            "022f8bde4d1a07209355b4a7250a5c5128e88b84bddc619ab7cba8d569b240efe4",
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+            "02e493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13",
+            "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+            "0379be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        ];
+        for rotation in 0..keys.len() {
+            let mut shuffled = keys.clone();
+            shuffled.rotate_left(rotation);
+            if rotation % 2 == 1 {
+                shuffled.reverse();
+            }
+            sort_keys(&mut shuffled);
+            let sorted: Vec<String> = shuffled
+                .iter()
+                .map(|key| hex::encode(&key.sec(true).unwrap()))
+                .collect();
+            assert_eq!(sorted, expected);
+        }
+        assert_eq!(cmp_sec(&g, &-&g), Ordering::Less);
+        assert_eq!(cmp_sec(&g, &g.clone()), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        let mut sec = secp256k1::g().sec(false).unwrap();
+        sec[64] ^= 1;
+        assert!(Point::parse(&sec).is_err());
+        // x = 5 has no y on secp256k1
+        let mut sec = vec![0x02];
+        sec.extend(secp256k1::s256_field(ubig!(5)).unwrap().to_be_bytes(32));
+        assert!(Point::parse(&sec).is_err());
+    }
+
+    #[test]
+    fn test_neg_sub() {
+        let p = point(47, 71);
+        let inf = Point::infinity(fe(0), fe(7));
+        assert_eq!(-&p, point(47, 152));
+        assert!((&p + &(-&p)).is_infinity());
+        assert!((&p - &p).is_infinity());
+        assert_eq!(-inf.clone(), inf);
+        assert_eq!(&p - &inf, p);
+        assert_eq!(&(&p + &p) - &p, p);
+        // (6, 0) has order 2, so it is its own inverse
+        let q = Point::new(Some(fe(6)), Some(fe(0)), fe(0), fe(7)).unwrap();
+        assert_eq!(-q.clone(), q);
+        assert!((q.clone() - q).is_infinity());
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::HashSet;
+
+        fn hash_of(point: &Point) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            point.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let a = point(47, 71);
+        let b = Point::new(Some(fe(47)), Some(fe(71)), fe(0), fe(7)).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b));
+        let keys = [a, b, point(17, 56), Point::infinity(fe(0), fe(7))]
+            .iter()
+            .cloned()
+            .collect::<HashSet<Point>>();
+        assert_eq!(keys.len(), 3);
+        assert!(keys.contains(&point(17, 56)));
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        let compressed = secp256k1::g().sec(true).unwrap();
+        let uncompressed = secp256k1::g().sec(false).unwrap();
+        assert!(Point::parse(&[]).is_err());
+        // truncated at every length, and one byte too long
+        for len in 0..compressed.len() {
+            assert!(Point::parse(&compressed[..len]).is_err());
+        }
+        for len in 0..uncompressed.len() {
+            assert!(Point::parse(&uncompressed[..len]).is_err());
+        }
+        let mut long = compressed.clone();
+        long.push(0);
+        assert!(Point::parse(&long).is_err());
+        // a compressed prefix on an uncompressed body, and vice versa
+        let mut mixed = uncompressed.clone();
+        mixed[0] = 0x02;
+        assert!(Point::parse(&mixed).is_err());
+        let mut mixed = compressed.clone();
+        mixed[0] = 0x04;
+        assert!(Point::parse(&mixed).is_err());
+        // unknown prefixes
+        for prefix in [0x00, 0x01, 0x05, 0x06, 0x07, 0xff].iter() {
+            let mut sec = compressed.clone();
+            sec[0] = *prefix;
+            assert!(Point::parse(&sec).is_err());
+        }
+        // x = 5 is a quadratic non-residue: 5^3 + 7 has no square root
+        let mut sec = vec![0x03];
+        sec.extend(secp256k1::s256_field(ubig!(5)).unwrap().to_be_bytes(32));
+        assert!(Point::parse(&sec).is_err());
+        // x >= p
+        let mut sec = vec![0x02];
+        sec.extend_from_slice(&[0xff; 32]);
+        assert!(Point::parse(&sec).is_err());
+    }
+
+    #[test]
+    fn test_mul_add() {
+        let g = secp256k1::g();
+        let p = &g * &ubig!(0xdeadbeef);
+        let cases = [
+            (ubig!(0), ubig!(0)),
+            (ubig!(1), ubig!(0)),
+            (ubig!(0), ubig!(1)),
+            (ubig!(12345), ubig!(678910)),
+            (secp256k1::n() - ubig!(1), ubig!(0xffff_ffff_ffff)),
+            (UBig::from(u128::MAX), secp256k1::n() >> 3),
+        ];
+        for (u, v) in cases.iter() {
+            assert_eq!(Point::mul_add(u, &g, v, &p), &(&g * u) + &(&p * v));
+        }
+        // g == p, and u*g cancelling v*p
+        assert_eq!(
+            Point::mul_add(&ubig!(5), &g, &ubig!(7), &g),
+            &g * &ubig!(12)
+        );
+        assert!(Point::mul_add(&ubig!(1), &g, &(secp256k1::n() - ubig!(1)), &g).is_infinity());
+        // on a small curve too
+        let q = point(47, 71);
+        let r = point(17, 56);
+        assert_eq!(
+            Point::mul_add(&ubig!(9), &q, &ubig!(4), &r),
+            &(&q * &ubig!(9)) + &(&r * &ubig!(4))
+        );
+    }
+}
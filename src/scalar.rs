@@ -0,0 +1,207 @@
+use crate::ecc::{modpow, to_bytes32};
+use crate::errors::ValueError;
+use crate::secp256k1;
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::ToString, vec::Vec};
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub};
+use ibig::{ubig, UBig};
+
+/// An integer mod the secp256k1 group order `N`, such as a private key or a
+/// signature component. The value is always reduced, and every operation
+/// reduces its result again, so a `Scalar` cannot be mistaken for a field
+/// element or an unreduced integer.
+///
+/// There is no `AsRef<[u8]>`: the value lives in a `UBig`, not a byte
+/// buffer that could be borrowed, so `to_be_bytes` returns the 32-byte
+/// encoding by value instead.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Scalar(pub(crate) UBig);
+
+impl Scalar {
+    /// `num` reduced mod `N`. For values that must already be in range,
+    /// such as parsed keys, use `try_from` instead.
+    pub fn new(num: UBig) -> Scalar {
+        Scalar(num % secp256k1::n())
+    }
+
+    pub fn as_ubig(&self) -> &UBig {
+        &self.0
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        to_bytes32(&self.0)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == ubig!(0)
+    }
+
+    /// Whether the value is above `N/2`, the half BIP62 calls high-s.
+    pub fn is_high(&self) -> bool {
+        self.0 > secp256k1::n() >> 1
+    }
+
+    /// The multiplicative inverse, `self^(N-2)` since `N` is prime; `None`
+    /// for zero.
+    pub fn inverse(&self) -> Option<Scalar> {
+        let n = secp256k1::n();
+        match self.is_zero() {
+            true => None,
+            false => Some(Scalar(modpow(&self.0, &(&n - ubig!(2)), &n))),
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for Scalar {
+    type Error = ValueError;
+    /// Reads exactly 32 big-endian bytes. A value of `N` or more is an
+    /// error rather than being reduced, so a corrupt encoding is caught.
+    fn try_from(bytes: &[u8]) -> Result<Scalar, ValueError> {
+        if bytes.len() != 32 {
+            return Err(ValueError {
+                message: format!("scalar must be 32 bytes, got {}", bytes.len()),
+            });
+        }
+        let num = UBig::from_be_bytes(bytes);
+        match num < secp256k1::n() {
+            true => Ok(Scalar(num)),
+            false => Err(ValueError {
+                message: "scalar is not below the group order".to_string(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<Vec<u8>> for Scalar {
+    type Error = ValueError;
+    /// As for `&[u8]`.
+    fn try_from(bytes: Vec<u8>) -> Result<Scalar, ValueError> {
+        Scalar::try_from(&bytes[..])
+    }
+}
+
+impl fmt::Display for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{:064x}", self.0)
+    }
+}
+
+impl<'a> Add<&'a Scalar> for &'a Scalar {
+    type Output = Scalar;
+    fn add(self, rhs: &Scalar) -> Scalar {
+        Scalar::new(&self.0 + &rhs.0)
+    }
+}
+
+impl Add for Scalar {
+    type Output = Scalar;
+    fn add(self, rhs: Self) -> Self {
+        &self + &rhs
+    }
+}
+
+impl<'a> Sub<&'a Scalar> for &'a Scalar {
+    type Output = Scalar;
+    fn sub(self, rhs: &Scalar) -> Scalar {
+        // both sides are below N, so adding N keeps the difference unsigned
+        Scalar::new(&self.0 + secp256k1::n() - &rhs.0)
+    }
+}
+
+impl Sub for Scalar {
+    type Output = Scalar;
+    fn sub(self, rhs: Self) -> Self {
+        &self - &rhs
+    }
+}
+
+impl<'a> Mul<&'a Scalar> for &'a Scalar {
+    type Output = Scalar;
+    fn mul(self, rhs: &Scalar) -> Scalar {
+        Scalar::new(&self.0 * &rhs.0)
+    }
+}
+
+impl Mul for Scalar {
+    type Output = Scalar;
+    fn mul(self, rhs: Self) -> Self {
+        &self * &rhs
+    }
+}
+
+impl Neg for &Scalar {
+    type Output = Scalar;
+    fn neg(self) -> Scalar {
+        Scalar::new(secp256k1::n() - &self.0)
+    }
+}
+
+impl Neg for Scalar {
+    type Output = Scalar;
+    fn neg(self) -> Self {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new_reduces() {
+        let n = secp256k1::n();
+        assert!(Scalar::new(n.clone()).is_zero());
+        assert_eq!(Scalar::new(&n + ubig!(5)), Scalar::new(ubig!(5)));
+    }
+
+    #[test]
+    fn test_add_wraps() {
+        let n = secp256k1::n();
+        let max = Scalar::new(&n - ubig!(1));
+        let one = Scalar::new(ubig!(1));
+        assert!((&max + &one).is_zero());
+        assert_eq!(&max + &Scalar::new(ubig!(2)), one);
+        assert_eq!(&max + &max, Scalar::new(&n - ubig!(2)));
+        assert_eq!(&one - &Scalar::new(ubig!(2)), max);
+        assert_eq!(-&one, max);
+        assert!((-Scalar::new(ubig!(0))).is_zero());
+        assert_eq!(&max * &max, one);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let a = Scalar::new(ubig!(12345));
+        assert_eq!(&a * &a.inverse().unwrap(), Scalar::new(ubig!(1)));
+        assert_eq!(Scalar::new(ubig!(0)).inverse(), None);
+    }
+
+    #[test]
+    fn test_bytes() {
+        let a = Scalar::new(ubig!(0x1234));
+        let bytes = a.to_be_bytes();
+        assert_eq!(Scalar::try_from(&bytes[..]).unwrap(), a);
+        assert_eq!(Scalar::try_from(bytes.to_vec()).unwrap(), a);
+        assert!(Scalar::try_from(vec![0xff; 32]).is_err());
+        assert_eq!(
+            a.to_string(),
+            "0000000000000000000000000000000000000000000000000000000000001234"
+        );
+        assert_eq!(
+            Scalar::try_from(&[0xff; 32][..]).unwrap_err().message,
+            "scalar is not below the group order"
+        );
+        assert_eq!(
+            Scalar::try_from(&bytes[1..]).unwrap_err().message,
+            "scalar must be 32 bytes, got 31"
+        );
+    }
+
+    #[test]
+    fn test_is_high() {
+        let half = secp256k1::n() >> 1;
+        assert!(!Scalar::new(half.clone()).is_high());
+        assert!(Scalar::new(half + ubig!(1)).is_high());
+    }
+}
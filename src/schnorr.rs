@@ -0,0 +1,259 @@
+use crate::ecc::to_bytes32;
+use crate::generator::mul_generator;
+use crate::hash::tagged_hash;
+use crate::point::{Point, PointOps};
+use crate::secp256k1;
+use ibig::{ubig, UBig};
+
+/// The curve point with x-coordinate `x` and an even y, if there is one.
+pub(crate) fn lift_x(x: &UBig) -> Option<Point> {
+    let x = secp256k1::s256_field(x.clone()).ok()?;
+    Point::lift_x(&x).ok()
+}
+
+pub(crate) fn has_even_y(point: &Point) -> bool {
+    !point.y.as_ref().unwrap().num.bit(0)
+}
+
+pub(crate) fn x_bytes(point: &Point) -> [u8; 32] {
+    to_bytes32(&point.x.as_ref().unwrap().num)
+}
+
+/// BIP340 challenge `e = H_challenge(R.x || P.x || msg) mod n`.
+fn challenge(r: &[u8], pubkey_x: &[u8; 32], msg: &[u8; 32]) -> UBig {
+    let mut data = Vec::with_capacity(96);
+    data.extend_from_slice(r);
+    data.extend_from_slice(pubkey_x);
+    data.extend_from_slice(msg);
+    UBig::from_be_bytes(&tagged_hash("BIP0340/challenge", &data)) % secp256k1::n()
+}
+
+/// Produces a BIP340 Schnorr signature `R.x || s` over the 32-byte `msg`.
+/// `aux_rand` is mixed into the nonce as protection against side channels;
+/// all zeros is allowed but fresh randomness is preferable.
+///
+/// Panics if `secret` is not in the range 1 to N-1.
+pub fn sign(secret: &UBig, msg: &[u8; 32], aux_rand: &[u8; 32]) -> [u8; 64] {
+    let n = secp256k1::n();
+    assert!(
+        *secret != ubig!(0) && *secret < n,
+        "secret must be in the range 1 to N-1"
+    );
+    let point = mul_generator(secret);
+    // x-only keys imply an even y, so sign with whichever of d, n-d matches
+    let d = match has_even_y(&point) {
+        true => secret.clone(),
+        false => &n - secret,
+    };
+    let pubkey_x = x_bytes(&point);
+
+    let mut t = to_bytes32(&d);
+    for (t, a) in t
+        .iter_mut()
+        .zip(tagged_hash("BIP0340/aux", aux_rand).iter())
+    {
+        *t ^= a;
+    }
+    let mut nonce_data = Vec::with_capacity(96);
+    nonce_data.extend_from_slice(&t);
+    nonce_data.extend_from_slice(&pubkey_x);
+    nonce_data.extend_from_slice(msg);
+    let k0 = UBig::from_be_bytes(&tagged_hash("BIP0340/nonce", &nonce_data)) % &n;
+    assert!(k0 != ubig!(0), "derived nonce is zero");
+    let r = mul_generator(&k0);
+    let k = match has_even_y(&r) {
+        true => k0,
+        false => &n - k0,
+    };
+
+    let r_x = x_bytes(&r);
+    let e = challenge(&r_x, &pubkey_x, msg);
+    let s = (k + e * d) % &n;
+    let mut sig = [0u8; 64];
+    sig[..32].copy_from_slice(&r_x);
+    sig[32..].copy_from_slice(&to_bytes32(&s));
+    sig
+}
+
+/// Verifies a BIP340 signature against the x-only public key `pubkey_x`.
+pub fn verify(pubkey_x: &[u8; 32], msg: &[u8; 32], sig: &[u8; 64]) -> bool {
+    let n = secp256k1::n();
+    let point = match lift_x(&UBig::from_be_bytes(pubkey_x)) {
+        Some(point) => point,
+        None => return false,
+    };
+    let r = UBig::from_be_bytes(&sig[..32]);
+    let s = UBig::from_be_bytes(&sig[32..]);
+    if r >= secp256k1::p() || s >= n {
+        return false;
+    }
+    let e = challenge(&sig[..32], pubkey_x, msg);
+    // R = s*G - e*P
+    let total = Point::mul_add(&s, &secp256k1::g(), &(&n - e), &point);
+    match &total.x {
+        Some(x) => has_even_y(&total) && x.num == r,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hex;
+
+    fn array32(s: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        out.copy_from_slice(&hex::decode(s).unwrap());
+        out
+    }
+
+    fn array64(s: &str) -> [u8; 64] {
+        let mut out = [0u8; 64];
+        out.copy_from_slice(&hex::decode(s).unwrap());
+        out
+    }
+
+    // (secret key, public key, aux_rand, message, signature) from the BIP340
+    // test vectors 0-3
+    const SIGNING_VECTORS: [(&str, &str, &str, &str, &str); 4] = [
+        (
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0",
+        ),
+        (
+            "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+        ),
+        (
+            "C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9",
+            "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8",
+            "C87AA53824B4D7AE2EB035A2B5BBBCCC080E76CDC6D1692C4B0B62D798E6D906",
+            "7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C",
+            "5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1BAB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7",
+        ),
+        (
+            "0B432B2677937381AEF05BB02A66ECD012773062CF3FA2549E44F58ED2401710",
+            "25D1DFF95105F5253C4022F628A996AD3A0D95FBF21D468A1B33F8C160D8F517",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            "7EB0509757E246F19449885651611CB965ECC1A187DD51B64FDA1EDC9637D5EC97582B9CB13DB3933705B32BA982AF5AF25FD78881EBB32771FC5922EFC66EA3",
+        ),
+    ];
+
+    #[test]
+    fn test_sign() {
+        for (secret, pubkey, aux, msg, sig) in SIGNING_VECTORS.iter() {
+            let secret = UBig::from_str_radix(secret, 16).unwrap();
+            let (pubkey, aux, msg) = (array32(pubkey), array32(aux), array32(msg));
+            let sig = array64(sig);
+            assert_eq!(x_bytes(&secp256k1::g().scalar_mul(&secret)), pubkey);
+            assert_eq!(sign(&secret, &msg, &aux)[..], sig[..]);
+            assert!(verify(&pubkey, &msg, &sig));
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let dff1 = "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659";
+        let msg = "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89";
+        // (public key, message, signature, expected) from BIP340 vectors 4-14
+        let cases = [
+            (
+                "D69C3509BB99E412E68B0FE8544E72837DFA30746D8BE2AA65975F29D22DC7B9",
+                "4DF3C3F68FCC83B27E9D42C90431A72499F17875C81A599B566C9889B9696703",
+                "00000000000000000000003B78CE563F89A0ED9414F5AA28AD0D96D6795F9C6376AFB1548AF603B3EB45C9F8207DEE1060CB71C04E80F593060B07D28308D7F4",
+                true,
+            ),
+            // public key not on the curve
+            (
+                "EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34",
+                msg,
+                "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+                false,
+            ),
+            // R has odd y
+            (
+                dff1,
+                msg,
+                "FFF97BD5755EEEA420453A14355235D382F6472F8568A18B2F057A14602975563CC27944640AC607CD107AE10923D9EF7A73C643E166BE5EBEAFA34B1AC553E2",
+                false,
+            ),
+            // negated message
+            (
+                dff1,
+                msg,
+                "1FA62E331EDBC21C394792D2AB1100A7B432B013DF3F6FF4F99FCB33E0E1515F28890B3EDB6E7189B630448B515CE4F8622A954CFE545735AAEA5134FCCDB2BD",
+                false,
+            ),
+            // negated s
+            (
+                dff1,
+                msg,
+                "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769961764B3AA9B2FFCB6EF947B6887A226E8D7C93E00C5ED0C1834FF0D0C2E6DA6",
+                false,
+            ),
+            // s*G - e*P is infinite; x(inf) taken as 0
+            (
+                dff1,
+                msg,
+                "0000000000000000000000000000000000000000000000000000000000000000123DDA8328AF9C23A94C1FEECFD123BA4FB73476F0D594DCB65C6425BD186051",
+                false,
+            ),
+            // s*G - e*P is infinite; x(inf) taken as 1
+            (
+                dff1,
+                msg,
+                "00000000000000000000000000000000000000000000000000000000000000017615FBAF5AE28864013C099742DEADB4DBA87F11AC6754F93780D5A1837CF197",
+                false,
+            ),
+            // sig[0:32] is not an x-coordinate on the curve
+            (
+                dff1,
+                msg,
+                "4A298DACAE57395A15D0795DDBFD1DCB564DA82B0F269BC70A74F8220429BA1D69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+                false,
+            ),
+            // sig[0:32] equal to the field size
+            (
+                dff1,
+                msg,
+                "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+                false,
+            ),
+            // s equal to the curve order
+            (
+                dff1,
+                msg,
+                "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141",
+                false,
+            ),
+            // public key exceeds the field size
+            (
+                "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30",
+                msg,
+                "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+                false,
+            ),
+        ];
+        for (pubkey, msg, sig, expected) in cases.iter() {
+            assert_eq!(
+                verify(&array32(pubkey), &array32(msg), &array64(sig)),
+                *expected,
+                "signature {}",
+                sig
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sign_rejects_zero_secret() {
+        sign(&ubig!(0), &[0; 32], &[0; 32]);
+    }
+}
@@ -0,0 +1,238 @@
+use crate::ecc::{FieldElement, FieldElementOps};
+use crate::errors::{EccError, ValueError};
+use crate::point::{Point, PointOps};
+#[cfg(not(feature = "std"))]
+use alloc::string::ToString;
+use ibig::{ubig, IBig, UBig};
+
+const P_HEX: &str = "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
+const N_HEX: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+const GX_HEX: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+const GY_HEX: &str = "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+/// The field prime `P = 2^256 - 2^32 - 977`.
+pub fn p() -> UBig {
+    UBig::from_str_radix(P_HEX, 16).unwrap()
+}
+
+/// The order `N` of the group generated by `G`.
+pub fn n() -> UBig {
+    UBig::from_str_radix(N_HEX, 16).unwrap()
+}
+
+/// The curve coefficient `A = 0`.
+pub fn a() -> FieldElement {
+    FieldElement::from_reduced(ubig!(0), p())
+}
+
+/// The curve coefficient `B = 7`.
+pub fn b() -> FieldElement {
+    FieldElement::from_reduced(ubig!(7), p())
+}
+
+/// The generator point `G`.
+pub fn g() -> Point {
+    Point::new(
+        Some(FieldElement::from_hex(GX_HEX, p()).unwrap()),
+        Some(FieldElement::from_hex(GY_HEX, p()).unwrap()),
+        a(),
+        b(),
+    )
+    .unwrap()
+}
+
+/// Creates an element of the secp256k1 base field.
+pub fn s256_field(num: UBig) -> Result<FieldElement, EccError> {
+    FieldElement::new(num, p())
+}
+
+const LAMBDA_HEX: &str = "5363ad4cc05c30e0a5261c028812645a122e22ea20816678df02967c1b23bd72";
+const BETA_HEX: &str = "7ae96a2b657c07106e64479eac3434e99cf0497512f58995c1396c28719501ee";
+// short basis of the lattice {(x, y) : x + y*lambda == 0 mod N}:
+// (A1, -B1) and (A2, A1)
+const A1_HEX: &str = "3086d221a7d46bcde86c90e49284eb15";
+const B1_HEX: &str = "e4437ed6010e88286f547fa90abfe4c3";
+const A2_HEX: &str = "114ca50f7a8e2f3f657c1108d9d44cfd8";
+
+/// The eigenvalue `lambda` of the endomorphism: `lambda * (x, y)` is
+/// `(beta * x, y)` for every point.
+pub fn lambda() -> UBig {
+    UBig::from_str_radix(LAMBDA_HEX, 16).unwrap()
+}
+
+/// The cube root of unity `beta` in the base field that realizes
+/// multiplication by `lambda`.
+pub fn beta() -> FieldElement {
+    FieldElement::from_hex(BETA_HEX, p()).unwrap()
+}
+
+/// GLV decomposition: splits `k` (taken mod N) into `k1 + k2 * lambda`
+/// with `|k1|` and `|k2|` of at most 128 bits, which can be one more than
+/// an `i128` holds. Each coefficient of `k` in the lattice basis is
+/// rounded to the nearest integer and the remainder is the short vector.
+pub fn glv_split(k: &UBig) -> (IBig, IBig) {
+    let n = n();
+    let k = k % &n;
+    let hex = |s| UBig::from_str_radix(s, 16).unwrap();
+    let (a1, b1, a2) = (hex(A1_HEX), hex(B1_HEX), hex(A2_HEX));
+    let round_div = |num: UBig| (num * ubig!(2) + &n) / (&n * ubig!(2));
+    let c1 = round_div(&a1 * &k);
+    let c2 = round_div(&b1 * &k);
+    let k1 = IBig::from(k) - IBig::from(&c1 * &a1) - IBig::from(&c2 * &a2);
+    let k2 = IBig::from(&c1 * &b1) - IBig::from(&c2 * &a1);
+    (k1, k2)
+}
+
+/// Square root in the secp256k1 base field. `P % 4 == 3`, so a root of a
+/// residue is `fe^((P+1)/4)` and no Tonelli-Shanks search is needed; `None`
+/// if `fe` has no root. Panics if `fe` is not in this field.
+pub fn sqrt(fe: &FieldElement) -> Option<FieldElement> {
+    let p = p();
+    assert!(fe.prime == p, "not a secp256k1 field element");
+    let root = fe.clone().pow_ubig(&((&p + ubig!(1)) >> 2));
+    match &root * &root == *fe {
+        true => Some(root),
+        false => None,
+    }
+}
+
+/// Creates a point on secp256k1, checking it satisfies `y^2 = x^3 + 7`.
+pub fn s256_point(x: UBig, y: UBig) -> Result<Point, EccError> {
+    Point::new(Some(s256_field(x)?), Some(s256_field(y)?), a(), b())
+}
+
+/// Reads 32 big-endian bytes as a secret scalar. Following BIP32, a value
+/// of `N` or more is rejected rather than reduced, since reducing would
+/// make small scalars twice as likely; zero is rejected as well. A caller
+/// that wants the reduction can take the bytes mod `N` itself.
+pub fn scalar_from_bytes(bytes: &[u8; 32]) -> Result<UBig, ValueError> {
+    let scalar = UBig::from_be_bytes(bytes);
+    if scalar >= n() {
+        return Err(ValueError {
+            message: "scalar is not below the group order".to_string(),
+        });
+    }
+    if scalar == ubig!(0) {
+        return Err(ValueError {
+            message: "scalar is zero".to_string(),
+        });
+    }
+    Ok(scalar)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ibig::ops::UnsignedAbs;
+
+    #[test]
+    fn test_prime() {
+        assert_eq!(p(), ubig!(2).pow(256) - ubig!(2).pow(32) - ubig!(977));
+    }
+
+    #[test]
+    fn test_order() {
+        assert!((&g() * &n()).is_infinity());
+        assert_eq!(&g() * &(n() + ubig!(1)), g());
+    }
+
+    #[test]
+    fn test_s256_point() {
+        assert!(s256_point(ubig!(1), ubig!(2)).is_err());
+        assert!(s256_field(p()).is_err());
+        // 7 * G, from Programming Bitcoin chapter 3
+        let expected = s256_point(
+            UBig::from_str_radix(
+                "5cbdf0646e5db4eaa398f365f2ea7a0e3d419b7e0330e39ce92bddedcac4f9bc",
+                16,
+            )
+            .unwrap(),
+            UBig::from_str_radix(
+                "6aebca40ba255960a3178d6d861a54dba813d0b813fde7b5a5082628087264da",
+                16,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(&g() * &ubig!(7), expected);
+    }
+
+    #[test]
+    fn test_sqrt() {
+        let g = g();
+        let (gx, gy) = (g.x.unwrap(), g.y.unwrap());
+        let root = sqrt(&(gx.clone().pow(3) + b())).unwrap();
+        assert!(root == gy || root == -&gy);
+        assert_eq!(sqrt(&(&gy * &gy)).unwrap().pow(2), &gy * &gy);
+        assert_eq!(sqrt(&a()).unwrap(), a());
+        // 5^3 + 7 is not a square mod P
+        assert_eq!(sqrt(&s256_field(ubig!(132)).unwrap()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a secp256k1 field element")]
+    fn test_sqrt_other_field() {
+        sqrt(&FieldElement::from_u64(4, ubig!(223)));
+    }
+
+    #[test]
+    fn test_scalar_from_bytes() {
+        let to_bytes = |num: &UBig| {
+            let mut out = [0u8; 32];
+            let bytes = num.to_be_bytes();
+            out[32 - bytes.len()..].copy_from_slice(&bytes);
+            out
+        };
+        assert_eq!(scalar_from_bytes(&to_bytes(&ubig!(1))).unwrap(), ubig!(1));
+        let max = n() - ubig!(1);
+        assert_eq!(scalar_from_bytes(&to_bytes(&max)).unwrap(), max);
+        assert_eq!(
+            scalar_from_bytes(&to_bytes(&n())).unwrap_err().message,
+            "scalar is not below the group order"
+        );
+        assert!(scalar_from_bytes(&[0xff; 32]).is_err());
+        assert_eq!(
+            scalar_from_bytes(&[0u8; 32]).unwrap_err().message,
+            "scalar is zero"
+        );
+    }
+
+    #[test]
+    fn test_glv_constants() {
+        let beta = beta();
+        assert_eq!(beta.clone().pow(3), FieldElement::from_u64(1, p()));
+        assert!(!beta.is_one());
+        let g = g();
+        assert_eq!(
+            g.scalar_mul(&lambda()),
+            Point::new(Some(g.x.clone().unwrap() * beta), g.y.clone(), a(), b()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_glv_split() {
+        let n = n();
+        let mut scalars = vec![
+            ubig!(0),
+            ubig!(1),
+            &n - ubig!(1),
+            &n >> 1,
+            lambda(),
+            n.clone(),
+        ];
+        let mut seed = crate::hash::sha256(b"glv split");
+        for _ in 0..32 {
+            scalars.push(UBig::from_be_bytes(&seed) % &n);
+            seed = crate::hash::sha256(&seed);
+        }
+        let n_signed = IBig::from(&n);
+        for k in scalars.iter() {
+            let (k1, k2) = glv_split(k);
+            assert!((&k1).unsigned_abs().bit_len() <= 128);
+            assert!((&k2).unsigned_abs().bit_len() <= 128);
+            let recombined = (k1 + k2 * IBig::from(lambda())) % &n_signed;
+            let recombined = (recombined + &n_signed) % &n_signed;
+            assert_eq!(recombined, IBig::from(k % &n));
+        }
+    }
+}
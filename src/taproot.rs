@@ -0,0 +1,133 @@
+use crate::generator::mul_generator;
+use crate::hash::tagged_hash;
+use crate::schnorr::{has_even_y, lift_x, x_bytes};
+use crate::secp256k1;
+use ibig::{ubig, UBig};
+
+/// BIP341 tweak `t = H_TapTweak(P.x || merkle_root)`; a key-path-only
+/// output commits to the key alone.
+fn tap_tweak(internal_key_x: &[u8; 32], merkle_root: Option<[u8; 32]>) -> UBig {
+    let mut data = internal_key_x.to_vec();
+    if let Some(root) = merkle_root {
+        data.extend_from_slice(&root);
+    }
+    let t = UBig::from_be_bytes(&tagged_hash("TapTweak", &data));
+    assert!(t < secp256k1::n(), "tweak is not below the group order");
+    t
+}
+
+/// Computes the output key `Q = P + t*G` for the x-only internal key `P`,
+/// returning `Q`'s x coordinate and the parity of its y (1 if odd), which a
+/// script-path spend records in its control block.
+///
+/// Panics if `internal_key_x` is not the x coordinate of a curve point.
+pub fn tweak_pubkey(internal_key_x: &[u8; 32], merkle_root: Option<[u8; 32]>) -> ([u8; 32], u8) {
+    let internal =
+        lift_x(&UBig::from_be_bytes(internal_key_x)).expect("internal key is not on the curve");
+    let t = tap_tweak(internal_key_x, merkle_root);
+    let output = &internal + &mul_generator(&t);
+    let parity = match has_even_y(&output) {
+        true => 0,
+        false => 1,
+    };
+    (x_bytes(&output), parity)
+}
+
+/// The secret for the output key of `tweak_pubkey`: the internal secret,
+/// negated if its point has an odd y, plus the tweak (mod N). Signing with
+/// it gives a key-path spend.
+///
+/// Panics if `secret` is not in the range 1 to N-1.
+pub fn tweak_seckey(secret: &UBig, merkle_root: Option<[u8; 32]>) -> UBig {
+    let n = secp256k1::n();
+    assert!(
+        *secret != ubig!(0) && *secret < n,
+        "secret must be in the range 1 to N-1"
+    );
+    let internal = mul_generator(secret);
+    // the x-only internal key stands for the even-y point
+    let d = match has_even_y(&internal) {
+        true => secret.clone(),
+        false => &n - secret,
+    };
+    let t = tap_tweak(&x_bytes(&internal), merkle_root);
+    let tweaked = (d + t) % &n;
+    assert!(tweaked != ubig!(0), "tweaked secret is zero");
+    tweaked
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bech32::p2tr_address;
+    use crate::ecc::to_bytes32;
+    use crate::point::PointOps;
+    use crate::schnorr;
+
+    fn from_hex32(s: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn test_tweak_pubkey() {
+        // BIP341 wallet test vectors, scriptPubKey section
+        let (output, parity) = tweak_pubkey(
+            &from_hex32("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d"),
+            None,
+        );
+        assert_eq!(
+            output,
+            from_hex32("53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343")
+        );
+        assert_eq!(parity, 1);
+        assert_eq!(
+            p2tr_address(&output, false),
+            "bc1p2wsldez5mud2yam29q22wgfh9439spgduvct83k3pm50fcxa5dps59h4z5"
+        );
+
+        let (output, parity) = tweak_pubkey(
+            &from_hex32("187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27"),
+            Some(from_hex32(
+                "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21",
+            )),
+        );
+        assert_eq!(
+            output,
+            from_hex32("147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3")
+        );
+        assert_eq!(parity, 1);
+    }
+
+    #[test]
+    fn test_tweak_seckey() {
+        // BIP341 wallet test vectors, keyPathSpending section
+        let secret = UBig::from_be_bytes(&from_hex32(
+            "6b973d88838f27366ed61c9ad6367663045cb456e28335c109e30717ae0c6baa",
+        ));
+        let tweaked = tweak_seckey(&secret, None);
+        assert_eq!(
+            to_bytes32(&tweaked),
+            from_hex32("2405b971772ad26915c8dcdf10f238753a9b837e5f8e6a86fd7c0cce5b7296d9")
+        );
+        // the tweaked secret signs for the tweaked public key
+        let internal_x = x_bytes(&secp256k1::g().scalar_mul(&secret));
+        let (output, _) = tweak_pubkey(&internal_x, None);
+        assert_eq!(x_bytes(&secp256k1::g().scalar_mul(&tweaked)), output);
+        let msg = [7u8; 32];
+        let sig = schnorr::sign(&tweaked, &msg, &[0u8; 32]);
+        assert!(schnorr::verify(&output, &msg, &sig));
+    }
+
+    #[test]
+    #[should_panic(expected = "internal key is not on the curve")]
+    fn test_tweak_pubkey_invalid() {
+        // x = 5 has no y on secp256k1
+        let mut x = [0u8; 32];
+        x[31] = 5;
+        tweak_pubkey(&x, None);
+    }
+}
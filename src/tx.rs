@@ -0,0 +1,745 @@
+use crate::errors::ValueError;
+use crate::hash::hash256;
+use crate::le::{u32_from_le_bytes, u64_from_le_bytes};
+use crate::script::Script;
+use crate::varint::{encode_varint, read_varint};
+use std::io::{self, Read};
+
+/// Sighash flag committing to all inputs and outputs.
+pub const SIGHASH_ALL: u32 = 1;
+/// Sighash flag committing to the inputs but none of the outputs.
+pub const SIGHASH_NONE: u32 = 2;
+/// Sighash flag committing only to the output at the signed input's index.
+pub const SIGHASH_SINGLE: u32 = 3;
+/// Modifier restricting the commitment to the signed input alone.
+pub const SIGHASH_ANYONECANPAY: u32 = 0x80;
+
+/// The sighash types with defined meaning: a base type, optionally
+/// combined with `SIGHASH_ANYONECANPAY`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SighashType {
+    All,
+    None,
+    Single,
+    AllAnyoneCanPay,
+    NoneAnyoneCanPay,
+    SingleAnyoneCanPay,
+}
+
+impl SighashType {
+    /// Parses the 4-byte sighash type appended to a digest, or the trailing
+    /// byte of a signature widened to `u32`. Any bit outside a base type and
+    /// the `SIGHASH_ANYONECANPAY` flag is rejected rather than masked off.
+    pub fn from_u32(n: u32) -> Result<SighashType, ValueError> {
+        match n {
+            0x01 => Ok(SighashType::All),
+            0x02 => Ok(SighashType::None),
+            0x03 => Ok(SighashType::Single),
+            0x81 => Ok(SighashType::AllAnyoneCanPay),
+            0x82 => Ok(SighashType::NoneAnyoneCanPay),
+            0x83 => Ok(SighashType::SingleAnyoneCanPay),
+            _ => Err(ValueError {
+                message: format!("unknown sighash type {:#x}", n),
+            }),
+        }
+    }
+
+    pub fn to_u32(self) -> u32 {
+        match self {
+            SighashType::All => SIGHASH_ALL,
+            SighashType::None => SIGHASH_NONE,
+            SighashType::Single => SIGHASH_SINGLE,
+            SighashType::AllAnyoneCanPay => SIGHASH_ALL | SIGHASH_ANYONECANPAY,
+            SighashType::NoneAnyoneCanPay => SIGHASH_NONE | SIGHASH_ANYONECANPAY,
+            SighashType::SingleAnyoneCanPay => SIGHASH_SINGLE | SIGHASH_ANYONECANPAY,
+        }
+    }
+}
+
+/// A transaction input: the outpoint being spent, its unlocking script and
+/// the sequence number.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TxIn {
+    /// Previous txid in wire (internal) byte order, i.e. reversed relative to
+    /// how txids are usually displayed.
+    pub prev_tx: [u8; 32],
+    pub prev_index: u32,
+    pub script_sig: Vec<u8>,
+    pub sequence: u32,
+    /// Witness stack items, bottom first; empty for a legacy input.
+    pub witness: Vec<Vec<u8>>,
+}
+
+/// A transaction output: an amount in satoshis and its locking script.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TxOut {
+    pub amount: u64,
+    pub script_pubkey: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tx {
+    pub version: u32,
+    pub tx_ins: Vec<TxIn>,
+    pub tx_outs: Vec<TxOut>,
+    pub locktime: u32,
+}
+
+fn truncated(e: io::Error) -> ValueError {
+    ValueError {
+        message: format!("truncated transaction: {}", e),
+    }
+}
+
+/// Reads exactly `len` bytes. The buffer grows with the data actually
+/// read, so a corrupt length prefix on a short stream fails at the end of
+/// the stream instead of allocating its full size up front.
+fn read_bytes<R: Read>(reader: &mut R, len: usize) -> Result<Vec<u8>, ValueError> {
+    let mut buf = Vec::new();
+    reader
+        .take(len as u64)
+        .read_to_end(&mut buf)
+        .map_err(truncated)?;
+    match buf.len() == len {
+        true => Ok(buf),
+        false => Err(truncated(io::ErrorKind::UnexpectedEof.into())),
+    }
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, ValueError> {
+    u32_from_le_bytes(&read_bytes(reader, 4)?)
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, ValueError> {
+    u64_from_le_bytes(&read_bytes(reader, 8)?)
+}
+
+fn read_script<R: Read>(reader: &mut R) -> Result<Vec<u8>, ValueError> {
+    let len = read_varint(reader).map_err(truncated)?;
+    read_bytes(reader, len as usize)
+}
+
+fn write_script(out: &mut Vec<u8>, script: &[u8]) {
+    out.extend(encode_varint(script.len() as u64));
+    out.extend_from_slice(script);
+}
+
+impl TxIn {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<TxIn, ValueError> {
+        let mut prev_tx = [0u8; 32];
+        prev_tx.copy_from_slice(&read_bytes(reader, 32)?);
+        Ok(TxIn {
+            prev_tx,
+            prev_index: read_u32(reader)?,
+            script_sig: read_script(reader)?,
+            sequence: read_u32(reader)?,
+            witness: Vec::new(),
+        })
+    }
+
+    /// Reads this input's witness stack, which a segwit transaction stores
+    /// after its outputs.
+    fn parse_witness<R: Read>(&mut self, reader: &mut R) -> Result<(), ValueError> {
+        self.witness = (0..read_varint(reader).map_err(truncated)?)
+            .map(|_| read_script(reader))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(())
+    }
+
+    /// The witness stack as serialized after the outputs: an item count,
+    /// then each item with its length prefix.
+    fn serialize_witness(&self) -> Vec<u8> {
+        let mut out = encode_varint(self.witness.len() as u64);
+        for item in self.witness.iter() {
+            write_script(&mut out, item);
+        }
+        out
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = self.prev_tx.to_vec();
+        out.extend_from_slice(&self.prev_index.to_le_bytes());
+        write_script(&mut out, &self.script_sig);
+        out.extend_from_slice(&self.sequence.to_le_bytes());
+        out
+    }
+}
+
+impl TxOut {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<TxOut, ValueError> {
+        Ok(TxOut {
+            amount: read_u64(reader)?,
+            script_pubkey: read_script(reader)?,
+        })
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = self.amount.to_le_bytes().to_vec();
+        write_script(&mut out, &self.script_pubkey);
+        out
+    }
+}
+
+impl Tx {
+    /// Parses a legacy or BIP144 segwit transaction. It reads one
+    /// transaction from `reader`, consuming exactly its bytes, so it can be
+    /// called repeatedly on a stream such as a block's transaction list.
+    ///
+    /// A zero input count where a legacy transaction has its inputs is the
+    /// segwit marker; it must be followed by the flag `0x01`, and the
+    /// witness stacks are read after the outputs.
+    pub fn parse<R: Read>(reader: &mut R) -> Result<Tx, ValueError> {
+        let version = read_u32(reader)?;
+        let mut num_ins = read_varint(reader).map_err(truncated)?;
+        let segwit = num_ins == 0;
+        if segwit {
+            let flag = read_bytes(reader, 1)?[0];
+            if flag != 0x01 {
+                return Err(ValueError {
+                    message: format!("unknown segwit flag {:#04x}", flag),
+                });
+            }
+            num_ins = read_varint(reader).map_err(truncated)?;
+        }
+        let mut tx_ins = (0..num_ins)
+            .map(|_| TxIn::parse(reader))
+            .collect::<Result<Vec<_>, _>>()?;
+        let tx_outs = (0..read_varint(reader).map_err(truncated)?)
+            .map(|_| TxOut::parse(reader))
+            .collect::<Result<Vec<_>, _>>()?;
+        if segwit {
+            for tx_in in tx_ins.iter_mut() {
+                tx_in.parse_witness(reader)?;
+            }
+            // an all-empty witness section would be serialized back in the
+            // legacy form, so it is not accepted either
+            if tx_ins.iter().all(|tx_in| tx_in.witness.is_empty()) {
+                return Err(ValueError {
+                    message: "segwit marker without witness data".to_string(),
+                });
+            }
+        }
+        Ok(Tx {
+            version,
+            tx_ins,
+            tx_outs,
+            locktime: read_u32(reader)?,
+        })
+    }
+
+    /// Whether any input carries witness data, so the transaction
+    /// serializes in the segwit form.
+    pub fn is_segwit(&self) -> bool {
+        self.tx_ins.iter().any(|tx_in| !tx_in.witness.is_empty())
+    }
+
+    /// The full serialization: the segwit form with marker, flag and
+    /// witnesses when any input has a witness, otherwise the legacy form.
+    pub fn serialize(&self) -> Vec<u8> {
+        match self.is_segwit() {
+            true => self.serialize_with_witness(),
+            false => self.serialize_legacy(),
+        }
+    }
+
+    /// The serialization without marker, flag or witnesses, which is what
+    /// the txid and the legacy sighash commit to.
+    pub fn serialize_legacy(&self) -> Vec<u8> {
+        let mut out = self.version.to_le_bytes().to_vec();
+        self.serialize_body(&mut out);
+        out.extend_from_slice(&self.locktime.to_le_bytes());
+        out
+    }
+
+    fn serialize_with_witness(&self) -> Vec<u8> {
+        let mut out = self.version.to_le_bytes().to_vec();
+        out.extend_from_slice(&[0x00, 0x01]);
+        self.serialize_body(&mut out);
+        for tx_in in self.tx_ins.iter() {
+            out.extend(tx_in.serialize_witness());
+        }
+        out.extend_from_slice(&self.locktime.to_le_bytes());
+        out
+    }
+
+    fn serialize_body(&self, out: &mut Vec<u8>) {
+        out.extend(encode_varint(self.tx_ins.len() as u64));
+        for tx_in in self.tx_ins.iter() {
+            out.extend(tx_in.serialize());
+        }
+        out.extend(encode_varint(self.tx_outs.len() as u64));
+        for tx_out in self.tx_outs.iter() {
+            out.extend(tx_out.serialize());
+        }
+    }
+
+    /// Double SHA-256 of the legacy serialization, byte-reversed into the
+    /// order block explorers display. Witnesses are left out, so signing
+    /// a segwit input does not change the txid.
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hash = hash256(&self.serialize_legacy());
+        hash.reverse();
+        hash
+    }
+
+    /// The txid as a hex string.
+    pub fn id(&self) -> String {
+        self.hash().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// The fee paid, given the amounts of the outputs spent by each input in
+    /// order: the input total minus the output total. Fails if `prevouts`
+    /// does not have one amount per input, if a total overflows, or if the
+    /// outputs spend more than the inputs provide.
+    pub fn fee(&self, prevouts: &[u64]) -> Result<u64, ValueError> {
+        if prevouts.len() != self.tx_ins.len() {
+            return Err(ValueError {
+                message: format!(
+                    "{} prevout amounts for {} inputs",
+                    prevouts.len(),
+                    self.tx_ins.len()
+                ),
+            });
+        }
+        let overflow = || ValueError {
+            message: "amount total overflows".to_string(),
+        };
+        let input_total = prevouts
+            .iter()
+            .try_fold(0u64, |sum, amount| sum.checked_add(*amount))
+            .ok_or_else(overflow)?;
+        let output_total = self
+            .tx_outs
+            .iter()
+            .try_fold(0u64, |sum, tx_out| sum.checked_add(tx_out.amount))
+            .ok_or_else(overflow)?;
+        input_total
+            .checked_sub(output_total)
+            .ok_or_else(|| ValueError {
+                message: format!(
+                    "outputs total {} exceeds inputs total {}",
+                    output_total, input_total
+                ),
+            })
+    }
+
+    /// Legacy `SIGHASH_ALL` digest for signing input `input_index`: every
+    /// other input's script is emptied, the signed input's is replaced by the
+    /// `script_pubkey` it spends, and the 4-byte hash type is appended
+    /// before `hash256`.
+    ///
+    /// Panics if `input_index` is out of range.
+    pub fn sig_hash(&self, input_index: usize, script_pubkey: &Script) -> [u8; 32] {
+        assert!(input_index < self.tx_ins.len(), "input index out of range");
+        let mut tx = self.clone();
+        for (i, tx_in) in tx.tx_ins.iter_mut().enumerate() {
+            tx_in.script_sig = match i == input_index {
+                true => script_pubkey.raw_serialize(),
+                false => Vec::new(),
+            };
+        }
+        let mut data = tx.serialize_legacy();
+        data.extend_from_slice(&SIGHASH_ALL.to_le_bytes());
+        hash256(&data)
+    }
+
+    /// BIP143 digest for signing segwit v0 input `input_index`. Unlike the
+    /// legacy digest it commits to the `amount` being spent, and the
+    /// `hashPrevouts`, `hashSequence` and `hashOutputs` components are
+    /// zeroed as `sighash_type` requires. `script_code` is serialized with
+    /// its length prefix. Fails if `sighash_type` is not one that
+    /// `SighashType::from_u32` accepts.
+    ///
+    /// Panics if `input_index` is out of range.
+    pub fn sig_hash_bip143(
+        &self,
+        input_index: usize,
+        script_code: &Script,
+        amount: u64,
+        sighash_type: u32,
+    ) -> Result<[u8; 32], ValueError> {
+        assert!(input_index < self.tx_ins.len(), "input index out of range");
+        let sighash_type = SighashType::from_u32(sighash_type)?;
+        let anyone_can_pay = matches!(
+            sighash_type,
+            SighashType::AllAnyoneCanPay
+                | SighashType::NoneAnyoneCanPay
+                | SighashType::SingleAnyoneCanPay
+        );
+        let single = matches!(
+            sighash_type,
+            SighashType::Single | SighashType::SingleAnyoneCanPay
+        );
+        let none = matches!(
+            sighash_type,
+            SighashType::None | SighashType::NoneAnyoneCanPay
+        );
+        let hash_prevouts = match anyone_can_pay {
+            true => [0u8; 32],
+            false => {
+                let mut data = Vec::new();
+                for tx_in in self.tx_ins.iter() {
+                    data.extend_from_slice(&tx_in.prev_tx);
+                    data.extend_from_slice(&tx_in.prev_index.to_le_bytes());
+                }
+                hash256(&data)
+            }
+        };
+        let hash_sequence = match anyone_can_pay || single || none {
+            true => [0u8; 32],
+            false => {
+                let mut data = Vec::new();
+                for tx_in in self.tx_ins.iter() {
+                    data.extend_from_slice(&tx_in.sequence.to_le_bytes());
+                }
+                hash256(&data)
+            }
+        };
+        let hash_outputs = if !single && !none {
+            let mut data = Vec::new();
+            for tx_out in self.tx_outs.iter() {
+                data.extend(tx_out.serialize());
+            }
+            hash256(&data)
+        } else if single && input_index < self.tx_outs.len() {
+            hash256(&self.tx_outs[input_index].serialize())
+        } else {
+            [0u8; 32]
+        };
+        let tx_in = &self.tx_ins[input_index];
+        let mut data = self.version.to_le_bytes().to_vec();
+        data.extend_from_slice(&hash_prevouts);
+        data.extend_from_slice(&hash_sequence);
+        data.extend_from_slice(&tx_in.prev_tx);
+        data.extend_from_slice(&tx_in.prev_index.to_le_bytes());
+        data.extend(script_code.serialize());
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&tx_in.sequence.to_le_bytes());
+        data.extend_from_slice(&hash_outputs);
+        data.extend_from_slice(&self.locktime.to_le_bytes());
+        data.extend_from_slice(&sighash_type.to_u32().to_le_bytes());
+        Ok(hash256(&data))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecdsa::{verify, Signature};
+    use crate::hex;
+    use crate::point::{Point, PointOps};
+    use crate::script::Command;
+    use ibig::UBig;
+    use std::io::Cursor;
+
+    // mainnet 452c629d67e41baec3ac6f04fe744b4b9617f8f859c63b3002f8684e7a4fee03
+    const RAW_TX: &str = "0100000001813f79011acb80925dfe69b3def355fe914bd1d96a3f5f71bf8303c6a989c7d1000000006b483045022100ed81ff192e75a3fd2304004dcadb746fa5e24c5031ccfcf21320b0277457c98f02207a986d955c6e0cb35d446a89d3f56100f4d7f67801c31967743a9c8e10615bed01210349fc4e631e3624a545de3f89f5d8684c7b8138bd94bdd531d2e213bf016b278afeffffff02a135ef01000000001976a914bc3b654dca7e56b04dca18f2566cdaf02e8d9ada88ac99c39800000000001976a9141c4bc762dd5423e332166702cb75f40df79fea1288ac19430600";
+
+    #[test]
+    fn test_parse() {
+        let tx = Tx::parse(&mut Cursor::new(hex::decode(RAW_TX).unwrap())).unwrap();
+        assert_eq!(tx.version, 1);
+        assert_eq!(tx.tx_ins.len(), 1);
+        let tx_in = &tx.tx_ins[0];
+        assert_eq!(
+            hex::encode(&tx_in.prev_tx),
+            "813f79011acb80925dfe69b3def355fe914bd1d96a3f5f71bf8303c6a989c7d1"
+        );
+        assert_eq!(tx_in.prev_index, 0);
+        assert_eq!(tx_in.script_sig.len(), 0x6b);
+        assert_eq!(tx_in.sequence, 0xfffffffe);
+        assert_eq!(tx.tx_outs.len(), 2);
+        assert_eq!(tx.tx_outs[0].amount, 32454049);
+        assert_eq!(
+            hex::encode(&tx.tx_outs[0].script_pubkey),
+            "76a914bc3b654dca7e56b04dca18f2566cdaf02e8d9ada88ac"
+        );
+        assert_eq!(tx.tx_outs[1].amount, 10011545);
+        assert_eq!(tx.locktime, 410393);
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let raw = hex::decode(RAW_TX).unwrap();
+        let tx = Tx::parse(&mut Cursor::new(&raw)).unwrap();
+        assert_eq!(tx.serialize().len(), raw.len());
+        assert_eq!(hex::encode(&tx.serialize()), RAW_TX);
+    }
+
+    #[test]
+    fn test_parse_truncated() {
+        let raw = hex::decode(RAW_TX).unwrap();
+        assert!(Tx::parse(&mut Cursor::new(&raw[..raw.len() - 1])).is_err());
+        assert!(Tx::parse(&mut Cursor::new(&raw[..10])).is_err());
+    }
+
+    /// Hands out one byte per `read` call, like a slow socket.
+    struct ByteReader<R: Read>(R);
+
+    impl<R: Read> Read for ByteReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(1);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    #[test]
+    fn test_parse_streaming() {
+        let mut stream = hex::decode(RAW_TX).unwrap();
+        stream.extend(hex::decode(SEGWIT_TX).unwrap());
+        stream.push(0xff);
+        let mut cursor = Cursor::new(&stream);
+        let legacy = Tx::parse(&mut cursor).unwrap();
+        assert_eq!(cursor.position() as usize, RAW_TX.len() / 2);
+        let segwit = Tx::parse(&mut cursor).unwrap();
+        assert_eq!(cursor.position() as usize, stream.len() - 1);
+
+        let mut reader = ByteReader(&stream[..]);
+        assert_eq!(Tx::parse(&mut reader).unwrap(), legacy);
+        assert_eq!(Tx::parse(&mut reader).unwrap(), segwit);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, [0xff]);
+    }
+
+    #[test]
+    fn test_parse_oversized_length() {
+        // one input whose scriptSig claims 0xffffffff bytes
+        let mut raw = hex::decode(RAW_TX).unwrap();
+        raw.truncate(4 + 1 + 36);
+        raw.extend_from_slice(&[0xfe, 0xff, 0xff, 0xff, 0xff, 0x00]);
+        assert!(Tx::parse(&mut ByteReader(&raw[..]))
+            .unwrap_err()
+            .message
+            .starts_with("truncated transaction"));
+    }
+
+    #[test]
+    fn test_id() {
+        let tx = Tx::parse(&mut Cursor::new(hex::decode(RAW_TX).unwrap())).unwrap();
+        assert_eq!(
+            tx.id(),
+            "452c629d67e41baec3ac6f04fe744b4b9617f8f859c63b3002f8684e7a4fee03"
+        );
+        assert_eq!(hex::encode(&tx.hash()), tx.id());
+    }
+
+    // the BIP143 native P2WPKH example, signed
+    const SEGWIT_TX: &str = "01000000000102fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f00000000494830450221008b9d1dc26ba6a9cb62127b02742fa9d754cd3bebf337f7a55d114c8e5cdd30be022040529b194ba3f9281a99f2b1c0a19c0489bc22ede944ccf4ecbab4cc618ef3ed01eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac000247304402203609e17b84f6a7d30c80bfa610b5b4542f32a8a0d5447a12fb1366d7f01cc44a0220573a954c4518331561406f90300e8f3358f51928d43c212a8caed02de67eebee0121025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee635711000000";
+
+    #[test]
+    fn test_parse_segwit() {
+        let raw = hex::decode(SEGWIT_TX).unwrap();
+        let tx = Tx::parse(&mut Cursor::new(&raw)).unwrap();
+        assert!(tx.is_segwit());
+        assert_eq!(tx.tx_ins.len(), 2);
+        assert_eq!(tx.tx_outs.len(), 2);
+        assert_eq!(tx.locktime, 17);
+        assert!(tx.tx_ins[0].witness.is_empty());
+        assert_eq!(tx.tx_ins[1].witness.len(), 2);
+        assert_eq!(
+            hex::encode(&tx.tx_ins[1].witness[1]),
+            "025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee6357"
+        );
+        assert_eq!(tx.serialize(), raw);
+
+        // the txid covers only the stripped form
+        assert_eq!(tx.serialize_legacy().len(), 233);
+        assert_eq!(
+            tx.id(),
+            "e8151a2af31c368a35053ddd4bdb285a8595c769a3ad83e0fa02314a602d4609"
+        );
+        let mut stripped = tx.clone();
+        stripped.tx_ins[1].witness.clear();
+        assert!(!stripped.is_segwit());
+        assert_eq!(stripped.serialize(), tx.serialize_legacy());
+        assert_eq!(stripped.id(), tx.id());
+
+        // the witness signature is over the BIP143 digest of input 1
+        let script_code = Script::from_raw(
+            &hex::decode("76a9141d0f172a0ecb48aee1be1f2687d2963ae33f71a188ac").unwrap(),
+        )
+        .unwrap();
+        let z = tx
+            .sig_hash_bip143(1, &script_code, 600000000, SIGHASH_ALL)
+            .unwrap();
+        let witness = &tx.tx_ins[1].witness;
+        let sig = Signature::parse(&witness[0][..witness[0].len() - 1]).unwrap();
+        let point = Point::parse(&witness[1]).unwrap();
+        assert!(verify(&point, &UBig::from_be_bytes(&z), &sig));
+
+        // and the legacy P2PK input 0 signs the stripped form
+        let script_pubkey = Script::from_raw(
+            &hex::decode("2103c9f4836b9a4f77fc0d81f7bcb01b7f1b35916864b9476c241ce9fc198bd25432ac")
+                .unwrap(),
+        )
+        .unwrap();
+        let script_sig = Script::from_raw(&tx.tx_ins[0].script_sig).unwrap();
+        assert!((&script_sig + &script_pubkey).evaluate(&tx.sig_hash(0, &script_pubkey)));
+    }
+
+    #[test]
+    fn test_parse_segwit_invalid() {
+        let raw = hex::decode(SEGWIT_TX).unwrap();
+        let mut bad_flag = raw.clone();
+        bad_flag[5] = 0x02;
+        assert_eq!(
+            Tx::parse(&mut Cursor::new(&bad_flag)).unwrap_err().message,
+            "unknown segwit flag 0x02"
+        );
+        assert!(Tx::parse(&mut Cursor::new(&raw[..raw.len() - 5])).is_err());
+        // marker and flag in front of a legacy body, with empty witnesses
+        let legacy = hex::decode(RAW_TX).unwrap();
+        let mut empty = legacy[..4].to_vec();
+        empty.extend_from_slice(&[0x00, 0x01]);
+        empty.extend_from_slice(&legacy[4..legacy.len() - 4]);
+        empty.push(0x00);
+        empty.extend_from_slice(&legacy[legacy.len() - 4..]);
+        assert_eq!(
+            Tx::parse(&mut Cursor::new(&empty)).unwrap_err().message,
+            "segwit marker without witness data"
+        );
+    }
+
+    #[test]
+    fn test_fee() {
+        let tx = Tx::parse(&mut Cursor::new(hex::decode(SEGWIT_TX).unwrap())).unwrap();
+        // BIP143 gives the spent amounts as 6.25 and 6 BTC
+        assert_eq!(tx.fee(&[625000000, 600000000]).unwrap(), 889210000);
+        assert_eq!(tx.fee(&[335790000, 0]).unwrap(), 0);
+        assert_eq!(
+            tx.fee(&[100000000, 100000000]).unwrap_err().message,
+            "outputs total 335790000 exceeds inputs total 200000000"
+        );
+        assert_eq!(
+            tx.fee(&[625000000]).unwrap_err().message,
+            "1 prevout amounts for 2 inputs"
+        );
+        assert!(tx.fee(&[u64::MAX, 1]).is_err());
+    }
+
+    #[test]
+    fn test_sig_hash() {
+        let tx = Tx::parse(&mut Cursor::new(hex::decode(RAW_TX).unwrap())).unwrap();
+        let script_pubkey = Script::from_raw(
+            &hex::decode("76a914a802fc56c704ce87c42d7c92eb75e7896bdc41ae88ac").unwrap(),
+        )
+        .unwrap();
+        let z = tx.sig_hash(0, &script_pubkey);
+        assert_eq!(
+            hex::encode(&z),
+            "27e0c5994dec7824e56dec6b2fcb342eb7cdb0d0957c2fce9882f715e85d81a6"
+        );
+        // the input's own signature verifies against that digest
+        let script_sig = Script::from_raw(&tx.tx_ins[0].script_sig).unwrap();
+        let (der, sec) = match &script_sig.cmds[..] {
+            [Command::Data(sig), Command::Data(sec)] => (&sig[..sig.len() - 1], sec),
+            _ => panic!("unexpected scriptSig"),
+        };
+        let sig = Signature::parse(der).unwrap();
+        let point = Point::parse(sec).unwrap();
+        assert!(verify(&point, &UBig::from_be_bytes(&z), &sig));
+        assert!((&script_sig + &script_pubkey).evaluate(&z));
+    }
+
+    // BIP143 native P2WPKH example, unsigned
+    const BIP143_P2WPKH_TX: &str = "0100000002fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f0000000000eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac11000000";
+
+    #[test]
+    fn test_sig_hash_bip143() {
+        let tx = Tx::parse(&mut Cursor::new(hex::decode(BIP143_P2WPKH_TX).unwrap())).unwrap();
+        let script_code = Script::from_raw(
+            &hex::decode("76a9141d0f172a0ecb48aee1be1f2687d2963ae33f71a188ac").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(
+                &tx.sig_hash_bip143(1, &script_code, 600000000, SIGHASH_ALL)
+                    .unwrap()
+            ),
+            "c37af31116d1b27caf68aae9e3ac82f1477929014d5b917657d0eb49478cb670"
+        );
+        // the spent amount is part of the digest
+        assert_ne!(
+            tx.sig_hash_bip143(1, &script_code, 600000001, SIGHASH_ALL)
+                .unwrap(),
+            tx.sig_hash_bip143(1, &script_code, 600000000, SIGHASH_ALL)
+                .unwrap()
+        );
+
+        // BIP143 P2SH-P2WPKH example
+        let tx = Tx::parse(&mut Cursor::new(hex::decode("0100000001db6b1b20aa0fd7b23880be2ecbd4a98130974cf4748fb66092ac4d3ceb1a54770100000000feffffff02b8b4eb0b000000001976a914a457b684d7f0d539a46a45bbc043f35b59d0d96388ac0008af2f000000001976a914fd270b1ee6abcaea97fea7ad0402e8bd8ad6d77c88ac92040000").unwrap())).unwrap();
+        let script_code = Script::from_raw(
+            &hex::decode("76a91479091972186c449eb1ded22b78e40d009bdf008988ac").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(
+                &tx.sig_hash_bip143(0, &script_code, 1000000000, SIGHASH_ALL)
+                    .unwrap()
+            ),
+            "64f3b0f4dd2bb3aa1ce8566d220cc74dda9df97d8490cc81d89d735c92e59fb6"
+        );
+    }
+
+    #[test]
+    fn test_sig_hash_bip143_flags() {
+        let tx = Tx::parse(&mut Cursor::new(hex::decode(BIP143_P2WPKH_TX).unwrap())).unwrap();
+        let script_code = Script::from_raw(
+            &hex::decode("76a9141d0f172a0ecb48aee1be1f2687d2963ae33f71a188ac").unwrap(),
+        )
+        .unwrap();
+        let cases = [
+            (
+                SIGHASH_NONE,
+                "6ff11a9b87fb510a3a31af006bd3811b632f8a39d88a2bfda49cee203dcc356e",
+            ),
+            (
+                SIGHASH_SINGLE,
+                "f4fe57286dd2ca8ac0e3dfccd54c352fcdcacbed80f194e264b75d7a7c74e4ce",
+            ),
+            (
+                SIGHASH_ALL | SIGHASH_ANYONECANPAY,
+                "fc5b6bbc855883bcfdaefb77071740ccde4929f15e6a13286584e779b2529d91",
+            ),
+            (
+                SIGHASH_SINGLE | SIGHASH_ANYONECANPAY,
+                "79ff9ff708f79ce8f7a4f90d62028533a99d7340b7fb3d819dfd9a599a78e39c",
+            ),
+        ];
+        for (sighash_type, expected) in cases.iter() {
+            let z = tx
+                .sig_hash_bip143(1, &script_code, 600000000, *sighash_type)
+                .unwrap();
+            assert_eq!(hex::encode(&z), *expected);
+        }
+        for sighash_type in [0x00, 0x04, 0x84].iter() {
+            assert_eq!(
+                tx.sig_hash_bip143(1, &script_code, 600000000, *sighash_type)
+                    .unwrap_err()
+                    .message,
+                format!("unknown sighash type {:#x}", sighash_type)
+            );
+        }
+    }
+
+    #[test]
+    fn test_sighash_type() {
+        assert_eq!(SighashType::from_u32(0x01).unwrap(), SighashType::All);
+        assert_eq!(
+            SighashType::from_u32(0x83).unwrap(),
+            SighashType::SingleAnyoneCanPay
+        );
+        assert!(SighashType::from_u32(0x04).is_err());
+        for n in [0x00, 0x80, 0x84, 0x41, 0x101, 0x8000_0001].iter() {
+            assert!(SighashType::from_u32(*n).is_err(), "{:#x}", n);
+        }
+        for n in [0x01, 0x02, 0x03, 0x81, 0x82, 0x83].iter() {
+            assert_eq!(SighashType::from_u32(*n).unwrap().to_u32(), *n);
+        }
+        assert_eq!(
+            SighashType::from_u32(0x04).unwrap_err().message,
+            "unknown sighash type 0x4"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::io::{self, Read};
+
+/// Encodes `n` as a Bitcoin CompactSize: values below `0xfd` are a single
+/// byte, larger ones get a `0xfd`/`0xfe`/`0xff` prefix followed by a 2, 4 or
+/// 8 byte little-endian value.
+pub fn encode_varint(n: u64) -> Vec<u8> {
+    match n {
+        0..=0xfc => vec![n as u8],
+        0xfd..=0xffff => [&[0xfd], &(n as u16).to_le_bytes()[..]].concat(),
+        0x10000..=0xffff_ffff => [&[0xfe], &(n as u32).to_le_bytes()[..]].concat(),
+        _ => [&[0xff], &n.to_le_bytes()[..]].concat(),
+    }
+}
+
+/// Reads a CompactSize, consuming exactly the prefix and its payload.
+pub fn read_varint(reader: &mut impl Read) -> io::Result<u64> {
+    let mut prefix = [0u8; 1];
+    reader.read_exact(&mut prefix)?;
+    let len = match prefix[0] {
+        0xfd => 2,
+        0xfe => 4,
+        0xff => 8,
+        n => return Ok(u64::from(n)),
+    };
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf[..len])?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_encode_varint() {
+        let cases: [(u64, &[u8]); 8] = [
+            (0, &[0x00]),
+            (0xfc, &[0xfc]),
+            (0xfd, &[0xfd, 0xfd, 0x00]),
+            (0xffff, &[0xfd, 0xff, 0xff]),
+            (0x10000, &[0xfe, 0x00, 0x00, 0x01, 0x00]),
+            (0xffffffff, &[0xfe, 0xff, 0xff, 0xff, 0xff]),
+            (
+                0x100000000,
+                &[0xff, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00],
+            ),
+            (
+                u64::MAX,
+                &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            ),
+        ];
+        for (n, encoded) in cases.iter() {
+            assert_eq!(encode_varint(*n), *encoded);
+            let mut reader = Cursor::new(encoded);
+            assert_eq!(read_varint(&mut reader).unwrap(), *n);
+            assert_eq!(reader.position() as usize, encoded.len());
+        }
+    }
+
+    #[test]
+    fn test_read_varint_truncated() {
+        assert!(read_varint(&mut Cursor::new(&[])).is_err());
+        assert!(read_varint(&mut Cursor::new(&[0xfd, 0x01])).is_err());
+        assert!(read_varint(&mut Cursor::new(&[0xfe, 0x01, 0x02, 0x03])).is_err());
+    }
+}
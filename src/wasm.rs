@@ -0,0 +1,120 @@
+//! JavaScript bindings. `UBig` and `Point` have no JS counterpart, so every
+//! number, key and SEC encoding crosses the boundary as a hex string and
+//! every failure as a thrown error carrying the `ValueError` message.
+//!
+//! The manifest keeps the default crate type so `no_std` builds need no
+//! allocator or panic handler; build the JS package with
+//! `cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib`
+//! and run `wasm-bindgen` on the output.
+
+use crate::ecc::{FieldElement, FieldElementOps};
+use crate::ecdsa::PrivateKey;
+use crate::errors::ValueError;
+use crate::hex;
+use crate::point::{sec_is_compressed, Point, PointOps};
+use ibig::UBig;
+use wasm_bindgen::prelude::*;
+
+fn to_js(err: ValueError) -> JsError {
+    JsError::new(&err.message)
+}
+
+fn parse_secret(secret_hex: &str) -> Result<PrivateKey, ValueError> {
+    PrivateKey::new(UBig::from_be_bytes(&hex::decode(secret_hex)?))
+}
+
+/// Checks that `num_hex` is an element of the field of `prime_hex` and
+/// returns it padded to the byte width of the prime.
+#[wasm_bindgen]
+pub fn field_element(num_hex: &str, prime_hex: &str) -> Result<String, JsError> {
+    let prime = UBig::from_be_bytes(&hex::decode(prime_hex).map_err(to_js)?);
+    let len = prime.bit_len().div_ceil(8);
+    let fe = FieldElement::from_hex(num_hex, prime).map_err(to_js)?;
+    Ok(hex::encode(&fe.to_be_bytes(len)))
+}
+
+/// The SEC encoding of the public key for a hex private key.
+#[wasm_bindgen]
+pub fn public_key(secret_hex: &str, compressed: bool) -> Result<String, JsError> {
+    let key = parse_secret(secret_hex).map_err(to_js)?;
+    let sec = key
+        .public_key()
+        .sec(compressed)
+        .map_err(|e| to_js(e.into()))?;
+    Ok(hex::encode(&sec))
+}
+
+/// The P2PKH address of a hex private key.
+#[wasm_bindgen]
+pub fn private_key_address(
+    secret_hex: &str,
+    compressed: bool,
+    testnet: bool,
+) -> Result<String, JsError> {
+    let key = parse_secret(secret_hex).map_err(to_js)?;
+    key.public_key()
+        .address(compressed, testnet)
+        .map_err(|e| to_js(e.into()))
+}
+
+/// Parses a hex SEC public key and returns its P2PKH address, keeping the
+/// key's compression.
+#[wasm_bindgen]
+pub fn sec_address(sec_hex: &str, testnet: bool) -> Result<String, JsError> {
+    let sec = hex::decode(sec_hex).map_err(to_js)?;
+    let point = Point::parse(&sec).map_err(to_js)?;
+    point
+        .address(sec_is_compressed(&sec) == Some(true), testnet)
+        .map_err(|e| to_js(e.into()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    // JsError can only be built inside a JS host, so natively only the
+    // success paths run
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn test_private_key_address() {
+        // secret 5002 from Programming Bitcoin, chapter 4
+        let secret = "138a";
+        assert_eq!(
+            private_key_address(secret, false, true).unwrap(),
+            "mmTPbXQFxboEtNRkwfh6K51jvdtHLxGeMA"
+        );
+        let sec = public_key(secret, false).unwrap();
+        assert_eq!(
+            sec_address(&sec, true).unwrap(),
+            "mmTPbXQFxboEtNRkwfh6K51jvdtHLxGeMA"
+        );
+        let compressed = public_key(secret, true).unwrap();
+        assert_eq!(compressed.len(), 66);
+        assert_eq!(
+            sec_address(&compressed, false).unwrap(),
+            private_key_address(secret, true, false).unwrap()
+        );
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn test_field_element() {
+        assert_eq!(field_element("0a", "0d").unwrap(), "0a");
+        let p = "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
+        assert_eq!(
+            field_element("07", p).unwrap(),
+            format!("{}07", "0".repeat(62))
+        );
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test]
+    fn test_errors() {
+        assert!(field_element("0d", "0d").is_err());
+        assert!(public_key("00", true).is_err());
+        assert!(sec_address("04ab", false).is_err());
+        assert!(private_key_address("xyz", true, false).is_err());
+    }
+}